@@ -1,14 +1,43 @@
-use super::{AccountAchievement, Achievement, AllAccountAchievements, AllAchievementIDs, Dailies};
+use super::{
+    Account, AccountAchievement, AccountDailyCrafting, AccountMapChests, AccountWorldBosses,
+    Achievement, AchievementCategory, AchievementGroup, AllAccountAchievements,
+    AllAchievementCategoryIDs, AllAchievementGroupIDs, AllAchievementIDs, AllCharacters,
+    AllDailyCrafting, AllMapChests, AllWorldBosses, Build, Dailies, Item, ItemPrice, Mastery,
+    Recipe, RecipeSearch, RecipeSearchResults, Title, TomorrowDailies,
+};
 
 /// Represents how and where to access the requested data
 pub trait Endpoint<P> {
     /// Whether the endpoint requires an API key from the user
     const AUTHENTICATED: bool;
 
+    /// The path to every item of the endpoint, if it can be requested a page at a time
+    const PAGED_PATH: Option<&'static str> = None;
+
+    /// The path to the endpoint, if every item of it can be requested at once with `ids=all`.
+    /// Only endpoints with a small set of items allow it.
+    const ALL_PATH: Option<&'static str> = None;
+
     /// Build a url path to the endpoint from the provided parameters
     fn get_path(param: Vec<&P>) -> String;
+
+    /// Build a url path to a single page of the endpoint
+    fn get_page_path(page: usize, page_size: usize) -> Option<String> {
+        Self::PAGED_PATH.map(|path| format!("{}?page={}&page_size={}", path, page, page_size))
+    }
+
+    /// Build a url path to every item of the endpoint at once
+    fn get_all_path() -> Option<String> {
+        Self::ALL_PATH.map(|path| format!("{}?ids=all", path))
+    }
 }
 
+/// The most items the gateway returns in a single page
+pub const MAX_PAGE_SIZE: usize = 200;
+
+/// The most IDs the gateway accepts in a single request
+pub const MAX_IDS_PER_REQUEST: usize = 200;
+
 impl Endpoint<()> for AllAchievementIDs {
     const AUTHENTICATED: bool = false;
 
@@ -19,6 +48,7 @@ impl Endpoint<()> for AllAchievementIDs {
 
 impl Endpoint<usize> for Achievement {
     const AUTHENTICATED: bool = false;
+    const PAGED_PATH: Option<&'static str> = Some("v2/achievements");
 
     fn get_path(ids: Vec<&usize>) -> String {
         format!(
@@ -31,6 +61,52 @@ impl Endpoint<usize> for Achievement {
     }
 }
 
+impl Endpoint<()> for AllAchievementGroupIDs {
+    const AUTHENTICATED: bool = false;
+
+    fn get_path(_: Vec<&()>) -> String {
+        "v2/achievements/groups".to_string()
+    }
+}
+
+impl Endpoint<String> for AchievementGroup {
+    const AUTHENTICATED: bool = false;
+    const PAGED_PATH: Option<&'static str> = Some("v2/achievements/groups");
+
+    fn get_path(ids: Vec<&String>) -> String {
+        format!(
+            "v2/achievements/groups?ids={}",
+            ids.iter()
+                .map(|id| id.as_str())
+                .collect::<Vec<&str>>()
+                .join(",")
+        )
+    }
+}
+
+impl Endpoint<()> for AllAchievementCategoryIDs {
+    const AUTHENTICATED: bool = false;
+
+    fn get_path(_: Vec<&()>) -> String {
+        "v2/achievements/categories".to_string()
+    }
+}
+
+impl Endpoint<usize> for AchievementCategory {
+    const AUTHENTICATED: bool = false;
+    const PAGED_PATH: Option<&'static str> = Some("v2/achievements/categories");
+
+    fn get_path(ids: Vec<&usize>) -> String {
+        format!(
+            "v2/achievements/categories?ids={}",
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
+
 impl Endpoint<usize> for AccountAchievement {
     const AUTHENTICATED: bool = true;
 
@@ -60,3 +136,173 @@ impl Endpoint<()> for Dailies {
         "v2/achievements/daily".to_string()
     }
 }
+
+impl Endpoint<()> for TomorrowDailies {
+    const AUTHENTICATED: bool = false;
+
+    fn get_path(_: Vec<&()>) -> String {
+        "v2/achievements/daily/tomorrow".to_string()
+    }
+}
+
+impl Endpoint<()> for Account {
+    const AUTHENTICATED: bool = true;
+
+    fn get_path(_: Vec<&()>) -> String {
+        "v2/account".to_string()
+    }
+}
+
+impl Endpoint<()> for AllCharacters {
+    const AUTHENTICATED: bool = true;
+
+    fn get_path(_: Vec<&()>) -> String {
+        "v2/characters?ids=all".to_string()
+    }
+}
+
+impl Endpoint<()> for AllDailyCrafting {
+    const AUTHENTICATED: bool = false;
+
+    fn get_path(_: Vec<&()>) -> String {
+        "v2/dailycrafting".to_string()
+    }
+}
+
+impl Endpoint<()> for AccountDailyCrafting {
+    const AUTHENTICATED: bool = true;
+
+    fn get_path(_: Vec<&()>) -> String {
+        "v2/account/dailycrafting".to_string()
+    }
+}
+
+impl Endpoint<()> for AllMapChests {
+    const AUTHENTICATED: bool = false;
+
+    fn get_path(_: Vec<&()>) -> String {
+        "v2/mapchests".to_string()
+    }
+}
+
+impl Endpoint<()> for AccountMapChests {
+    const AUTHENTICATED: bool = true;
+
+    fn get_path(_: Vec<&()>) -> String {
+        "v2/account/mapchests".to_string()
+    }
+}
+
+impl Endpoint<()> for AllWorldBosses {
+    const AUTHENTICATED: bool = false;
+
+    fn get_path(_: Vec<&()>) -> String {
+        "v2/worldbosses".to_string()
+    }
+}
+
+impl Endpoint<()> for AccountWorldBosses {
+    const AUTHENTICATED: bool = true;
+
+    fn get_path(_: Vec<&()>) -> String {
+        "v2/account/worldbosses".to_string()
+    }
+}
+
+impl Endpoint<usize> for Item {
+    const AUTHENTICATED: bool = false;
+    const PAGED_PATH: Option<&'static str> = Some("v2/items");
+
+    fn get_path(ids: Vec<&usize>) -> String {
+        format!(
+            "v2/items?ids={}",
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
+
+impl Endpoint<usize> for Recipe {
+    const AUTHENTICATED: bool = false;
+    const PAGED_PATH: Option<&'static str> = Some("v2/recipes");
+
+    fn get_path(ids: Vec<&usize>) -> String {
+        format!(
+            "v2/recipes?ids={}",
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
+
+impl Endpoint<RecipeSearch> for RecipeSearchResults {
+    const AUTHENTICATED: bool = false;
+
+    // The search endpoint only accepts a single item so anything past the first is ignored
+    fn get_path(searches: Vec<&RecipeSearch>) -> String {
+        match searches.first() {
+            Some(RecipeSearch::Input(id)) => format!("v2/recipes/search?input={}", id),
+            Some(RecipeSearch::Output(id)) => format!("v2/recipes/search?output={}", id),
+            None => "v2/recipes/search".to_string(),
+        }
+    }
+}
+
+impl Endpoint<usize> for ItemPrice {
+    const AUTHENTICATED: bool = false;
+    const PAGED_PATH: Option<&'static str> = Some("v2/commerce/prices");
+
+    fn get_path(ids: Vec<&usize>) -> String {
+        format!(
+            "v2/commerce/prices?ids={}",
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
+
+impl Endpoint<usize> for Title {
+    const AUTHENTICATED: bool = false;
+    const PAGED_PATH: Option<&'static str> = Some("v2/titles");
+    const ALL_PATH: Option<&'static str> = Some("v2/titles");
+
+    fn get_path(ids: Vec<&usize>) -> String {
+        format!(
+            "v2/titles?ids={}",
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
+
+impl Endpoint<usize> for Mastery {
+    const AUTHENTICATED: bool = false;
+    const PAGED_PATH: Option<&'static str> = Some("v2/masteries");
+    const ALL_PATH: Option<&'static str> = Some("v2/masteries");
+
+    fn get_path(ids: Vec<&usize>) -> String {
+        format!(
+            "v2/masteries?ids={}",
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    }
+}
+
+impl Endpoint<()> for Build {
+    const AUTHENTICATED: bool = false;
+
+    fn get_path(_: Vec<&()>) -> String {
+        "v2/build".to_string()
+    }
+}
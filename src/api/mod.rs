@@ -2,7 +2,7 @@
 //!
 //! [Official documentation](https://wiki.guildwars2.com/wiki/API:Main)
 
-use std::{collections::HashSet, hash::Hash};
+use std::{collections::HashSet, fmt, hash::Hash};
 
 pub mod endpoints;
 
@@ -29,6 +29,29 @@ pub struct Achievement {
     pub point_cap: Option<i32>,
 }
 
+impl Achievement {
+    /// The total amount of achievement points across all tiers
+    pub fn total_points(&self) -> usize {
+        self.tiers.iter().map(|tier| tier.points).sum()
+    }
+
+    /// The amount of achievement points from tiers that have already been completed
+    pub fn earned_points(&self, account_achievement: Option<&AccountAchievement>) -> usize {
+        match account_achievement {
+            Some(account_achievement) if account_achievement.done => self.total_points(),
+            Some(account_achievement) => {
+                let current = account_achievement.current.unwrap_or_default();
+                self.tiers
+                    .iter()
+                    .filter(|tier| current >= tier.count)
+                    .map(|tier| tier.points)
+                    .sum()
+            }
+            None => 0,
+        }
+    }
+}
+
 impl PartialEq for Achievement {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id
@@ -41,6 +64,59 @@ impl Hash for Achievement {
     }
 }
 
+/// A list of all available IDs of achievement groups
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AllAchievementGroupIDs(pub Vec<String>);
+
+/// A top-level grouping of achievement categories, such as "Heart of Thorns"
+#[derive(Serialize, Deserialize, Eq, Clone, Debug)]
+pub struct AchievementGroup {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub order: usize,
+    pub categories: Vec<usize>,
+}
+
+impl PartialEq for AchievementGroup {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Hash for AchievementGroup {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+/// A list of all available IDs of achievement categories
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AllAchievementCategoryIDs(pub Vec<usize>);
+
+/// A category of achievements, such as "Slayer"
+#[derive(Serialize, Deserialize, Eq, Clone, Debug)]
+pub struct AchievementCategory {
+    pub id: usize,
+    pub name: String,
+    pub description: String,
+    pub order: usize,
+    pub icon: Option<String>,
+    pub achievements: Vec<usize>,
+}
+
+impl PartialEq for AchievementCategory {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Hash for AchievementCategory {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
 /// A list of all achievements with progress on the users account
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AllAccountAchievements(pub HashSet<AccountAchievement>);
@@ -57,6 +133,19 @@ pub struct AccountAchievement {
     pub unlocked: Option<bool>,
 }
 
+impl AccountAchievement {
+    /// How far along this achievement is, from `0.0` to `1.0`
+    pub fn progress(&self) -> f64 {
+        if self.done {
+            return 1.0;
+        }
+        match (self.current, self.max) {
+            (Some(current), Some(max)) if max > 0 => (current as f64 / max as f64).min(1.0),
+            _ => 0.0,
+        }
+    }
+}
+
 impl PartialEq for AccountAchievement {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id
@@ -92,6 +181,30 @@ pub struct AchievementBit {
     pub text: Option<String>,
 }
 
+/// IDs of all the items that can only be crafted once per day
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AllDailyCrafting(pub Vec<String>);
+
+/// IDs of the items the user has already crafted today
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AccountDailyCrafting(pub Vec<String>);
+
+/// IDs of all the map meta chests that can only be looted once per day
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AllMapChests(pub Vec<String>);
+
+/// IDs of the map meta chests the user has already looted today
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AccountMapChests(pub Vec<String>);
+
+/// IDs of all the world bosses that give a chest once per day
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AllWorldBosses(pub Vec<String>);
+
+/// IDs of the world bosses the user has already defeated today
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AccountWorldBosses(pub Vec<String>);
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Dailies {
     pub pve: Vec<Daily>,
@@ -101,6 +214,10 @@ pub struct Dailies {
     pub special: Vec<Daily>,
 }
 
+/// The daily achievements that become available after the next daily reset
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TomorrowDailies(pub Dailies);
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Daily {
     pub id: usize,
@@ -108,16 +225,51 @@ pub struct Daily {
     pub required_access: Option<RequiredAccess>,
 }
 
+impl Daily {
+    /// Whether the daily can be done by the account with at least one of its characters
+    pub fn is_available(&self, account: Option<&Account>, characters: &[Character]) -> bool {
+        let has_access = match (&self.required_access, account) {
+            (Some(required_access), Some(account)) => required_access.allows(account),
+            _ => true,
+        };
+        let has_level = characters.is_empty()
+            || characters
+                .iter()
+                .any(|character| self.level.contains(character.level));
+        has_access && has_level
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct LevelRange {
-    min: usize,
-    max: usize,
+    pub min: usize,
+    pub max: usize,
+}
+
+impl LevelRange {
+    pub fn contains(&self, level: usize) -> bool {
+        self.min <= level && level <= self.max
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct RequiredAccess {
-    product: Product,
-    condition: AccessCondition,
+    pub product: Product,
+    pub condition: AccessCondition,
+}
+
+impl RequiredAccess {
+    /// Whether the expansions the account owns satisfy this requirement
+    pub fn allows(&self, account: &Account) -> bool {
+        let owned = account.access.iter().any(|access| match self.product {
+            Product::HeartOfThorns => access == "HeartOfThorns",
+            Product::PathOfFire => access == "PathOfFire",
+        });
+        match self.condition {
+            AccessCondition::HasAccess => owned,
+            AccessCondition::NoAccess => !owned,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -131,3 +283,168 @@ pub enum AccessCondition {
     HasAccess,
     NoAccess,
 }
+
+/// General information about the users account
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Account {
+    pub id: String,
+    pub name: String,
+    /// The game and expansions the account owns, e.g. "GuildWars2" or "PathOfFire"
+    pub access: Vec<String>,
+}
+
+/// All the characters on the users account
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AllCharacters(pub Vec<Character>);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Character {
+    pub name: String,
+    pub profession: String,
+    pub level: usize,
+}
+
+/// Data about a specific item
+#[derive(Serialize, Deserialize, Eq, Clone, Debug)]
+pub struct Item {
+    pub id: usize,
+    pub chat_link: String,
+    pub name: String,
+    pub icon: Option<String>,
+    pub description: Option<String>,
+    #[serde(alias = "type")]
+    pub item_type: String,
+    pub rarity: String,
+    pub level: usize,
+    pub vendor_value: usize,
+    pub flags: Vec<String>,
+}
+
+impl PartialEq for Item {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Hash for Item {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+/// Data about a specific crafting recipe
+#[derive(Serialize, Deserialize, Eq, Clone, Debug)]
+pub struct Recipe {
+    pub id: usize,
+    #[serde(alias = "type")]
+    pub recipe_type: String,
+    pub output_item_id: usize,
+    pub output_item_count: usize,
+    pub disciplines: Vec<String>,
+    pub min_rating: usize,
+    pub flags: Vec<String>,
+    pub ingredients: Vec<RecipeIngredient>,
+    pub chat_link: String,
+}
+
+impl PartialEq for Recipe {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Hash for Recipe {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct RecipeIngredient {
+    pub item_id: usize,
+    pub count: usize,
+}
+
+/// Which side of a recipe to search for an item on
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
+pub enum RecipeSearch {
+    /// Recipes that consume the item as an ingredient
+    Input(usize),
+    /// Recipes that produce the item
+    Output(usize),
+}
+
+impl fmt::Display for RecipeSearch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecipeSearch::Input(id) => write!(f, "recipes that use item {}", id),
+            RecipeSearch::Output(id) => write!(f, "recipes that make item {}", id),
+        }
+    }
+}
+
+/// The IDs of recipes found by a [RecipeSearch]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecipeSearchResults(pub Vec<usize>);
+
+/// The current trading post prices of an item
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ItemPrice {
+    pub id: usize,
+    pub whitelisted: bool,
+    pub buys: Listing,
+    pub sells: Listing,
+}
+
+/// The best offer on one side of the trading post
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Listing {
+    pub quantity: usize,
+    pub unit_price: usize,
+}
+
+/// A title that can be displayed with the name of a character
+#[derive(Serialize, Deserialize, Eq, Clone, Debug)]
+pub struct Title {
+    pub id: usize,
+    pub name: String,
+}
+
+impl PartialEq for Title {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Hash for Title {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+/// A mastery track, such as "Gliding"
+#[derive(Serialize, Deserialize, Eq, Clone, Debug)]
+pub struct Mastery {
+    pub id: usize,
+    pub name: String,
+    pub requirement: String,
+    pub region: String,
+}
+
+impl PartialEq for Mastery {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Hash for Mastery {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+/// The current build of the game, which changes whenever the game is updated
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Build {
+    pub id: usize,
+}
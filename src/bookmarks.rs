@@ -1,11 +1,14 @@
 use std::{
-    collections::{hash_set::IntoIter, HashSet},
     hash::Hash,
     ops::{Deref, DerefMut},
+    vec::IntoIter,
 };
 
+use crate::chatlink::ChatLink;
+
+/// Bookmarks in the order the user arranged them
 #[derive(Serialize, Deserialize, Debug, Default)]
-pub struct Bookmarks(HashSet<Bookmark>);
+pub struct Bookmarks(Vec<Bookmark>);
 
 impl IntoIterator for Bookmarks {
     type Item = Bookmark;
@@ -17,7 +20,7 @@ impl IntoIterator for Bookmarks {
 }
 
 impl Deref for Bookmarks {
-    type Target = HashSet<Bookmark>;
+    type Target = Vec<Bookmark>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -34,12 +37,59 @@ impl DerefMut for Bookmarks {
 pub struct Bookmark {
     pub kind: BookmarkKind,
     pub name: String,
+    /// The chat code or URL that was bookmarked
     pub link: String,
+    #[serde(default)]
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
 pub enum BookmarkKind {
+    /// A waypoint or point of interest
     Waypoint,
+    Item(usize),
+    Recipe(usize),
+    Achievement(usize),
+    BuildTemplate,
+    Url,
+}
+
+impl BookmarkKind {
+    /// Work out what kind of bookmark a link is. Chat codes, web addresses and achievement IDs
+    /// can be bookmarked. Chat codes are also returned decoded.
+    pub fn from_link(link: &str) -> Result<(Self, Option<ChatLink>), String> {
+        let link = link.trim();
+        if link.starts_with("http://") || link.starts_with("https://") {
+            return Ok((BookmarkKind::Url, None));
+        }
+
+        if let Ok(id) = link.parse::<usize>() {
+            return Ok((BookmarkKind::Achievement(id), None));
+        }
+
+        let chat_link = ChatLink::decode(link).map_err(|err| err.to_string())?;
+        let kind = match &chat_link {
+            ChatLink::Map(_) => BookmarkKind::Waypoint,
+            ChatLink::Item { id, .. } => BookmarkKind::Item(*id),
+            ChatLink::Recipe(id) => BookmarkKind::Recipe(*id),
+            ChatLink::BuildTemplate(_) => BookmarkKind::BuildTemplate,
+            _ => return Err(format!("{} can't be bookmarked", chat_link.describe())),
+        };
+        Ok((kind, Some(chat_link)))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BookmarkKind::Waypoint => "Waypoint",
+            BookmarkKind::Item(_) => "Item",
+            BookmarkKind::Recipe(_) => "Recipe",
+            BookmarkKind::Achievement(_) => "Achievement",
+            BookmarkKind::BuildTemplate => "Build",
+            BookmarkKind::Url => "URL",
+        }
+    }
 }
 
 impl PartialEq for Bookmark {
@@ -54,8 +104,82 @@ impl Hash for Bookmark {
     }
 }
 
+impl Bookmark {
+    /// Whether the name, folder, any tag or the link contains `text`, ignoring case
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        self.name.to_lowercase().contains(&text)
+            || self.link.to_lowercase().contains(&text)
+            || self
+                .folder
+                .as_ref()
+                .is_some_and(|folder| folder.to_lowercase().contains(&text))
+            || self
+                .tags
+                .iter()
+                .any(|tag| tag.to_lowercase().contains(&text))
+    }
+}
+
 impl Bookmarks {
-    pub fn items(&self) -> &HashSet<Bookmark> {
+    pub fn items(&self) -> &Vec<Bookmark> {
         &self.0
     }
+
+    /// Add a bookmark to the end of the list. Returns false if one with the same name exists.
+    pub fn add(&mut self, bookmark: Bookmark) -> bool {
+        if self.0.contains(&bookmark) {
+            return false;
+        }
+        self.0.push(bookmark);
+        true
+    }
+
+    /// Remove a bookmark. Returns false if it wasn't bookmarked.
+    pub fn delete(&mut self, bookmark: &Bookmark) -> bool {
+        let len = self.0.len();
+        self.0.retain(|other| other != bookmark);
+        self.0.len() != len
+    }
+
+    /// Replace the bookmark named `name` with `bookmark`, keeping its place in the list.
+    /// Returns false if it doesn't exist, or if the new name is taken by another bookmark.
+    pub fn replace(&mut self, name: &str, bookmark: Bookmark) -> bool {
+        let index = match self.0.iter().position(|other| other.name == name) {
+            Some(index) => index,
+            None => return false,
+        };
+        if bookmark.name != name && self.0.contains(&bookmark) {
+            return false;
+        }
+        self.0[index] = bookmark;
+        true
+    }
+
+    /// Swap a bookmark with the previous or next one in the same folder. Returns false if it
+    /// is already first or last in the folder.
+    pub fn shift(&mut self, bookmark: &Bookmark, up: bool) -> bool {
+        let index = match self.0.iter().position(|other| other == bookmark) {
+            Some(index) => index,
+            None => return false,
+        };
+        let folder = &self.0[index].folder;
+        let neighbour = if up {
+            self.0[..index]
+                .iter()
+                .rposition(|other| &other.folder == folder)
+        } else {
+            self.0[index + 1..]
+                .iter()
+                .position(|other| &other.folder == folder)
+                .map(|offset| index + 1 + offset)
+        };
+        match neighbour {
+            Some(neighbour) => {
+                self.0.swap(index, neighbour);
+                true
+            }
+            None => false,
+        }
+    }
 }
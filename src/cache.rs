@@ -21,9 +21,19 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::api::AllAccountAchievements;
-use crate::api::Dailies;
+use crate::api::{Account, AllCharacters};
 use crate::api::{AccountAchievement, Achievement, AllAchievementIDs};
+use crate::api::{
+    AccountDailyCrafting, AccountMapChests, AccountWorldBosses, AllDailyCrafting, AllMapChests,
+    AllWorldBosses,
+};
+use crate::api::{
+    AchievementCategory, AchievementGroup, AllAchievementCategoryIDs, AllAchievementGroupIDs,
+};
+use crate::api::{Build, Dailies, TomorrowDailies};
+use crate::api::{Item, ItemPrice, Mastery, Recipe, RecipeSearch, RecipeSearchResults, Title};
 use crate::config::Config;
+use crate::reset::Reset;
 
 /// Controls all cached content for the app
 pub struct Cache {
@@ -33,43 +43,237 @@ pub struct Cache {
     compression: bool,
 }
 
+/// When a [CacheItem] should be requested from the endpoint again
+#[derive(Clone, Copy, Debug)]
+pub enum Expiry {
+    /// After a fixed amount of time
+    Age(Duration),
+    /// At the next daily reset at 00:00 UTC
+    DailyReset,
+    /// At the next weekly reset on Monday at 07:30 UTC
+    WeeklyReset,
+    /// When the game is updated. Until then, it's kept for the max cache age.
+    Build,
+}
+
 /// A single cached response from the server.
 // TODO Rename this or [CacheItem] to have more distinct names
 #[derive(Serialize, Deserialize, Clone, Debug)]
 struct CachedItem<T> {
     #[serde(with = "ts_seconds")]
     expires: DateTime<Utc>,
+    /// The game build this was fetched under, if it was known
+    #[serde(default)]
+    build: Option<usize>,
     inner: T,
 }
 
 impl<T> CachedItem<T> {
-    pub fn new(item: T, life: Duration) -> Self
+    pub fn new(item: T, expiry: Expiry, cache: &Cache) -> Self
     where
         T: Serialize + DeserializeOwned,
     {
+        Self::new_at(item, expiry, cache, Utc::now())
+    }
+
+    /// Cache an item as if it was fetched at `now`
+    fn new_at(item: T, expiry: Expiry, cache: &Cache, now: DateTime<Utc>) -> Self {
+        let expires = match expiry {
+            Expiry::Age(life) => now.add(life),
+            Expiry::DailyReset => Reset::Daily.next(now),
+            Expiry::WeeklyReset => Reset::Weekly.next(now),
+            Expiry::Build => now.add(cache.max_age),
+        };
         Self {
-            expires: Utc::now().add(life),
+            expires,
+            build: cache.build(),
             inner: item,
         }
     }
 
+    /// Expire this if it was fetched under a different game build, or before the build was known
+    fn expire_build(&mut self, build: usize) {
+        if self.build != Some(build) {
+            self.expires = Utc::now();
+        }
+    }
+
     fn expired(&self) -> bool {
-        Utc::now() > self.expires
+        self.expired_at(Utc::now())
+    }
+
+    // An item that expires at a reset is expired from the moment the reset happens
+    fn expired_at(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires
     }
 }
 
 /// The actual cached content
 #[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
 struct CacheContents {
     #[serde(skip)]
     invalid: RwLock<bool>, // TODO Should probably move this up to [Cache] somehow.
+    /// The last known game build
+    build: RwLock<Option<usize>>,
     all_achievements_ids: RwLock<Option<CachedItem<AllAchievementIDs>>>,
     achievements: RwLock<HashMap<usize, CachedItem<Achievement>>>,
+    all_achievement_group_ids: RwLock<Option<CachedItem<AllAchievementGroupIDs>>>,
+    achievement_groups: RwLock<HashMap<String, CachedItem<AchievementGroup>>>,
+    all_achievement_category_ids: RwLock<Option<CachedItem<AllAchievementCategoryIDs>>>,
+    achievement_categories: RwLock<HashMap<usize, CachedItem<AchievementCategory>>>,
     account_achievements: RwLock<Option<CachedItem<HashSet<AccountAchievement>>>>,
     dailies: RwLock<Option<CachedItem<Dailies>>>,
+    tomorrow_dailies: RwLock<Option<CachedItem<Dailies>>>,
+    account: RwLock<Option<CachedItem<Account>>>,
+    characters: RwLock<Option<CachedItem<AllCharacters>>>,
+    daily_crafting: RwLock<Option<CachedItem<AllDailyCrafting>>>,
+    account_daily_crafting: RwLock<Option<CachedItem<AccountDailyCrafting>>>,
+    map_chests: RwLock<Option<CachedItem<AllMapChests>>>,
+    account_map_chests: RwLock<Option<CachedItem<AccountMapChests>>>,
+    world_bosses: RwLock<Option<CachedItem<AllWorldBosses>>>,
+    account_world_bosses: RwLock<Option<CachedItem<AccountWorldBosses>>>,
+    items: RwLock<HashMap<usize, CachedItem<Item>>>,
+    recipes: RwLock<HashMap<usize, CachedItem<Recipe>>>,
+    item_prices: RwLock<HashMap<usize, CachedItem<ItemPrice>>>,
+    titles: RwLock<HashMap<usize, CachedItem<Title>>>,
+    masteries: RwLock<HashMap<usize, CachedItem<Mastery>>>,
+    /// Paths of single IDs the gateway had nothing for, so they aren't requested again
+    missing: RwLock<HashMap<String, CachedItem<()>>>,
+    /// Paths of endpoints that had every item requested at once, so they can all be taken from
+    /// the cache
+    complete: RwLock<HashMap<String, CachedItem<()>>>,
+}
+
+impl CacheContents {
+    // Anything about the game itself might have changed with an update. What's only about the
+    // account is left alone.
+    fn expire_build(&self, build: usize) {
+        expire_build(&self.all_achievements_ids, build);
+        expire_build_map(&self.achievements, build);
+        expire_build(&self.all_achievement_group_ids, build);
+        expire_build_map(&self.achievement_groups, build);
+        expire_build(&self.all_achievement_category_ids, build);
+        expire_build_map(&self.achievement_categories, build);
+        expire_build(&self.dailies, build);
+        expire_build(&self.tomorrow_dailies, build);
+        expire_build(&self.daily_crafting, build);
+        expire_build(&self.map_chests, build);
+        expire_build(&self.world_bosses, build);
+        expire_build_map(&self.items, build);
+        expire_build_map(&self.recipes, build);
+        expire_build_map(&self.item_prices, build);
+        expire_build_map(&self.titles, build);
+        expire_build_map(&self.masteries, build);
+        expire_build_map(&self.missing, build);
+        expire_build_map(&self.complete, build);
+    }
+}
+
+fn expire_build<T>(cached: &RwLock<Option<CachedItem<T>>>, build: usize) {
+    if let Ok(mut cached) = cached.write() {
+        if let Some(cached_item) = cached.as_mut() {
+            cached_item.expire_build(build);
+        }
+    }
+}
+
+fn all_from_map<K, T: Clone>(cached: &RwLock<HashMap<K, CachedItem<T>>>) -> Vec<T> {
+    cached
+        .read()
+        .map(|cached| {
+            cached
+                .values()
+                .filter(|cached_item| !cached_item.expired())
+                .map(|cached_item| cached_item.inner.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn expire_build_map<K, T>(cached: &RwLock<HashMap<K, CachedItem<T>>>, build: usize) {
+    if let Ok(mut cached) = cached.write() {
+        cached
+            .values_mut()
+            .for_each(|cached_item| cached_item.expire_build(build));
+    }
 }
 
 impl Cache {
+    /// The last known game build
+    pub fn build(&self) -> Option<usize> {
+        self.contents
+            .build
+            .read()
+            .map(|build| *build)
+            .ok()
+            .flatten()
+    }
+
+    /// Remember the current game build. Everything that isn't about the account and was fetched
+    /// under another build is expired, whenever it would expire otherwise.
+    pub fn set_build(&self, build: usize) {
+        if self.build() == Some(build) {
+            return;
+        }
+        let _lock = self
+            .contents
+            .build
+            .write()
+            .map(|mut cached_build| *cached_build = Some(build));
+        self.contents.expire_build(build);
+        let _lock = self
+            .contents
+            .invalid
+            .write()
+            .map(|mut invalid| *invalid = true);
+    }
+
+    /// Whether the gateway recently had nothing at this path
+    pub fn is_missing(&self, path: &str) -> bool {
+        self.contents
+            .missing
+            .read()
+            .map(|missing| missing.get(path).is_some_and(|cached| !cached.expired()))
+            .unwrap_or_default()
+    }
+
+    /// Remember that the gateway had nothing at this path. New IDs usually show up with game
+    /// updates, so it's asked again after the next one.
+    pub fn set_missing(&self, path: String) {
+        let cached_item = CachedItem::new((), Expiry::Build, self);
+        let _lock = self.contents.missing.write().map(|mut missing| {
+            missing.insert(path, cached_item);
+        });
+        let _lock = self
+            .contents
+            .invalid
+            .write()
+            .map(|mut invalid| *invalid = true);
+    }
+
+    /// Whether every item at this path was recently cached
+    pub fn is_complete(&self, path: &str) -> bool {
+        self.contents
+            .complete
+            .read()
+            .map(|complete| complete.get(path).is_some_and(|cached| !cached.expired()))
+            .unwrap_or_default()
+    }
+
+    /// Remember that every item at this path was cached. It expires along with the items.
+    pub fn set_complete(&self, path: String, expiry: Expiry) {
+        let cached_item = CachedItem::new((), expiry, self);
+        let _lock = self.contents.complete.write().map(|mut complete| {
+            complete.insert(path, cached_item);
+        });
+        let _lock = self
+            .contents
+            .invalid
+            .write()
+            .map(|mut invalid| *invalid = true);
+    }
+
     pub fn load(config: &Config) -> Self {
         let path = PathBuf::from(config.cache_path.clone());
         let contents = match Self::load_from_disk(&path, config.cache_compression) {
@@ -91,7 +295,7 @@ impl Cache {
         path: &Path,
         use_compression: bool,
     ) -> Result<CacheContents, Box<dyn std::error::Error>> {
-        let file = File::open(&path)?;
+        let file = File::open(path)?;
         let mut reader = BufReader::new(&file);
         let mut contents = String::new();
         if use_compression {
@@ -137,6 +341,22 @@ pub trait CacheItem<P> {
     where
         Self: Sized;
 
+    /// Every item of this kind in the cache, for endpoints that can be requested all at once
+    fn all_from_cache(_cache: &Cache) -> Vec<Self>
+    where
+        Self: Sized,
+    {
+        vec![]
+    }
+
+    /// How long this [CacheItem] is kept in the cache
+    fn expiry(cache: &Cache) -> Expiry
+    where
+        Self: Sized,
+    {
+        Expiry::Age(cache.max_age)
+    }
+
     /// Marks this [CacheItem] as invalid so it is requested from the endpoint next time it is accessed
     fn invalidate_cache(cache: &Cache) {
         let _lock = cache
@@ -160,13 +380,17 @@ impl CacheItem<()> for AllAchievementIDs {
             .map(|cached_item| cached_item.inner)
     }
 
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Build
+    }
+
     fn to_cache(&self, cache: &Cache) {
         let _lock = cache
             .contents
             .all_achievements_ids
             .write()
             .map(|mut cached_item| {
-                *cached_item = Some(CachedItem::new(self.clone(), cache.max_age))
+                *cached_item = Some(CachedItem::new(self.clone(), Self::expiry(cache), cache))
             });
         Self::invalidate_cache(cache);
     }
@@ -185,10 +409,134 @@ impl CacheItem<usize> for Achievement {
             .map(|cached_item| cached_item.inner)
     }
 
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Build
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache.contents.achievements.write().map(|mut cached| {
+            cached.insert(
+                self.id,
+                CachedItem::new(self.clone(), Self::expiry(cache), cache),
+            )
+        });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl CacheItem<()> for AllAchievementGroupIDs {
+    fn from_cache(cache: &Cache, _: &()) -> Option<AllAchievementGroupIDs> {
+        cache
+            .contents
+            .all_achievement_group_ids
+            .read()
+            .map(|cache| cache.clone())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Build
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache
+            .contents
+            .all_achievement_group_ids
+            .write()
+            .map(|mut cached_item| {
+                *cached_item = Some(CachedItem::new(self.clone(), Self::expiry(cache), cache))
+            });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl CacheItem<String> for AchievementGroup {
+    fn from_cache(cache: &Cache, id: &String) -> Option<AchievementGroup> {
+        cache
+            .contents
+            .achievement_groups
+            .read()
+            .map(|cache| cache.get(id).cloned())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Build
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache.contents.achievement_groups.write().map(|mut cached| {
+            cached.insert(
+                self.id.clone(),
+                CachedItem::new(self.clone(), Self::expiry(cache), cache),
+            )
+        });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl CacheItem<()> for AllAchievementCategoryIDs {
+    fn from_cache(cache: &Cache, _: &()) -> Option<AllAchievementCategoryIDs> {
+        cache
+            .contents
+            .all_achievement_category_ids
+            .read()
+            .map(|cache| cache.clone())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Build
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache
+            .contents
+            .all_achievement_category_ids
+            .write()
+            .map(|mut cached_item| {
+                *cached_item = Some(CachedItem::new(self.clone(), Self::expiry(cache), cache))
+            });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl CacheItem<usize> for AchievementCategory {
+    fn from_cache(cache: &Cache, id: &usize) -> Option<AchievementCategory> {
+        cache
+            .contents
+            .achievement_categories
+            .read()
+            .map(|cache| cache.get(id).cloned())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Build
+    }
+
     fn to_cache(&self, cache: &Cache) {
-        let _lock =
-            cache.contents.achievements.write().map(|mut cached| {
-                cached.insert(self.id, CachedItem::new(self.clone(), cache.max_age))
+        let _lock = cache
+            .contents
+            .achievement_categories
+            .write()
+            .map(|mut cached| {
+                cached.insert(
+                    self.id,
+                    CachedItem::new(self.clone(), Self::expiry(cache), cache),
+                )
             });
         Self::invalidate_cache(cache);
     }
@@ -210,13 +558,17 @@ impl CacheItem<()> for AllAccountAchievements {
             .flatten()
     }
 
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Age(Duration::minutes(1))
+    }
+
     fn to_cache(&self, cache: &Cache) {
         let _lock = cache
             .contents
             .account_achievements
             .write()
             .map(|mut cached| {
-                *cached = Some(CachedItem::new(self.0.clone(), Duration::minutes(1)))
+                *cached = Some(CachedItem::new(self.0.clone(), Self::expiry(cache), cache))
             });
         Self::invalidate_cache(cache);
     }
@@ -232,14 +584,13 @@ impl CacheItem<usize> for AccountAchievement {
                 cache
                     .as_ref()
                     .filter(|cached_item| !cached_item.expired())
-                    .map(|cached_item| {
+                    .and_then(|cached_item| {
                         cached_item
                             .inner
                             .iter()
                             .find(|a| a.id == *id)
                             .map(ToOwned::to_owned)
                     })
-                    .flatten()
             })
             .ok()
             .flatten()
@@ -261,10 +612,631 @@ impl CacheItem<()> for Dailies {
             .map(|cached_item| cached_item.inner)
     }
 
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::DailyReset
+    }
+
     fn to_cache(&self, cache: &Cache) {
         let _lock = cache.contents.dailies.write().map(|mut cached_item| {
-            *cached_item = Some(CachedItem::new(self.clone(), cache.max_age))
+            *cached_item = Some(CachedItem::new(self.clone(), Self::expiry(cache), cache))
         });
         Self::invalidate_cache(cache);
     }
 }
+
+impl CacheItem<()> for TomorrowDailies {
+    fn from_cache(cache: &Cache, _: &()) -> Option<TomorrowDailies> {
+        cache
+            .contents
+            .tomorrow_dailies
+            .read()
+            .map(|cache| cache.clone())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| TomorrowDailies(cached_item.inner))
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::DailyReset
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache
+            .contents
+            .tomorrow_dailies
+            .write()
+            .map(|mut cached_item| {
+                *cached_item = Some(CachedItem::new(self.0.clone(), Self::expiry(cache), cache))
+            });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl CacheItem<()> for Account {
+    fn from_cache(cache: &Cache, _: &()) -> Option<Account> {
+        cache
+            .contents
+            .account
+            .read()
+            .map(|cache| cache.clone())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Age(Duration::minutes(1))
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache.contents.account.write().map(|mut cached_item| {
+            *cached_item = Some(CachedItem::new(self.clone(), Self::expiry(cache), cache))
+        });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl CacheItem<()> for AllCharacters {
+    fn from_cache(cache: &Cache, _: &()) -> Option<AllCharacters> {
+        cache
+            .contents
+            .characters
+            .read()
+            .map(|cache| cache.clone())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Age(Duration::minutes(1))
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache.contents.characters.write().map(|mut cached_item| {
+            *cached_item = Some(CachedItem::new(self.clone(), Self::expiry(cache), cache))
+        });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl CacheItem<()> for AllDailyCrafting {
+    fn from_cache(cache: &Cache, _: &()) -> Option<AllDailyCrafting> {
+        cache
+            .contents
+            .daily_crafting
+            .read()
+            .map(|cache| cache.clone())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Build
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache
+            .contents
+            .daily_crafting
+            .write()
+            .map(|mut cached_item| {
+                *cached_item = Some(CachedItem::new(self.clone(), Self::expiry(cache), cache))
+            });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl CacheItem<()> for AccountDailyCrafting {
+    fn from_cache(cache: &Cache, _: &()) -> Option<AccountDailyCrafting> {
+        cache
+            .contents
+            .account_daily_crafting
+            .read()
+            .map(|cache| cache.clone())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Age(Duration::minutes(1))
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache
+            .contents
+            .account_daily_crafting
+            .write()
+            .map(|mut cached_item| {
+                *cached_item = Some(CachedItem::new(self.clone(), Self::expiry(cache), cache))
+            });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl CacheItem<()> for AllMapChests {
+    fn from_cache(cache: &Cache, _: &()) -> Option<AllMapChests> {
+        cache
+            .contents
+            .map_chests
+            .read()
+            .map(|cache| cache.clone())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Build
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache.contents.map_chests.write().map(|mut cached_item| {
+            *cached_item = Some(CachedItem::new(self.clone(), Self::expiry(cache), cache))
+        });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl CacheItem<()> for AccountMapChests {
+    fn from_cache(cache: &Cache, _: &()) -> Option<AccountMapChests> {
+        cache
+            .contents
+            .account_map_chests
+            .read()
+            .map(|cache| cache.clone())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Age(Duration::minutes(1))
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache
+            .contents
+            .account_map_chests
+            .write()
+            .map(|mut cached_item| {
+                *cached_item = Some(CachedItem::new(self.clone(), Self::expiry(cache), cache))
+            });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl CacheItem<()> for AllWorldBosses {
+    fn from_cache(cache: &Cache, _: &()) -> Option<AllWorldBosses> {
+        cache
+            .contents
+            .world_bosses
+            .read()
+            .map(|cache| cache.clone())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Build
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache.contents.world_bosses.write().map(|mut cached_item| {
+            *cached_item = Some(CachedItem::new(self.clone(), Self::expiry(cache), cache))
+        });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl CacheItem<()> for AccountWorldBosses {
+    fn from_cache(cache: &Cache, _: &()) -> Option<AccountWorldBosses> {
+        cache
+            .contents
+            .account_world_bosses
+            .read()
+            .map(|cache| cache.clone())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Age(Duration::minutes(1))
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache
+            .contents
+            .account_world_bosses
+            .write()
+            .map(|mut cached_item| {
+                *cached_item = Some(CachedItem::new(self.clone(), Self::expiry(cache), cache))
+            });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl CacheItem<usize> for Item {
+    fn from_cache(cache: &Cache, id: &usize) -> Option<Item> {
+        cache
+            .contents
+            .items
+            .read()
+            .map(|cache| cache.get(id).cloned())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Build
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache.contents.items.write().map(|mut cached| {
+            cached.insert(
+                self.id,
+                CachedItem::new(self.clone(), Self::expiry(cache), cache),
+            )
+        });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl Item {
+    /// Find every unexpired [Item] in the cache with a name that contains `name`
+    ///
+    /// The gateway has no way to search items by name, so this can only find items that have been
+    /// requested before, either on their own or while walking every page of items.
+    pub fn search_cache(cache: &Cache, name: &str) -> Vec<Item> {
+        let name = name.to_lowercase();
+        cache
+            .contents
+            .items
+            .read()
+            .map(|cache| {
+                cache
+                    .values()
+                    .filter(|cached_item| !cached_item.expired())
+                    .filter(|cached_item| cached_item.inner.name.to_lowercase().contains(&name))
+                    .map(|cached_item| cached_item.inner.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl CacheItem<usize> for Recipe {
+    fn from_cache(cache: &Cache, id: &usize) -> Option<Recipe> {
+        cache
+            .contents
+            .recipes
+            .read()
+            .map(|cache| cache.get(id).cloned())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Build
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache.contents.recipes.write().map(|mut cached| {
+            cached.insert(
+                self.id,
+                CachedItem::new(self.clone(), Self::expiry(cache), cache),
+            )
+        });
+        Self::invalidate_cache(cache);
+    }
+}
+
+// Search results aren't keyed by anything the response contains, so they're never cached. The
+// recipes they point to are.
+impl CacheItem<RecipeSearch> for RecipeSearchResults {
+    fn from_cache(_cache: &Cache, _search: &RecipeSearch) -> Option<RecipeSearchResults> {
+        None
+    }
+
+    fn to_cache(&self, _cache: &Cache) {}
+}
+
+impl CacheItem<usize> for ItemPrice {
+    fn from_cache(cache: &Cache, id: &usize) -> Option<ItemPrice> {
+        cache
+            .contents
+            .item_prices
+            .read()
+            .map(|cache| cache.get(id).cloned())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Age(Duration::minutes(5))
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache.contents.item_prices.write().map(|mut cached| {
+            cached.insert(
+                self.id,
+                CachedItem::new(self.clone(), Self::expiry(cache), cache),
+            )
+        });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl CacheItem<usize> for Title {
+    fn from_cache(cache: &Cache, id: &usize) -> Option<Title> {
+        cache
+            .contents
+            .titles
+            .read()
+            .map(|cache| cache.get(id).cloned())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn all_from_cache(cache: &Cache) -> Vec<Title> {
+        all_from_map(&cache.contents.titles)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Build
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache.contents.titles.write().map(|mut cached| {
+            cached.insert(
+                self.id,
+                CachedItem::new(self.clone(), Self::expiry(cache), cache),
+            )
+        });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl CacheItem<usize> for Mastery {
+    fn from_cache(cache: &Cache, id: &usize) -> Option<Mastery> {
+        cache
+            .contents
+            .masteries
+            .read()
+            .map(|cache| cache.get(id).cloned())
+            .ok()
+            .flatten()
+            .filter(|cached_item| !cached_item.expired())
+            .map(|cached_item| cached_item.inner)
+    }
+
+    fn all_from_cache(cache: &Cache) -> Vec<Mastery> {
+        all_from_map(&cache.contents.masteries)
+    }
+
+    fn expiry(_: &Cache) -> Expiry {
+        Expiry::Build
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        let _lock = cache.contents.masteries.write().map(|mut cached| {
+            cached.insert(
+                self.id,
+                CachedItem::new(self.clone(), Self::expiry(cache), cache),
+            )
+        });
+        Self::invalidate_cache(cache);
+    }
+}
+
+impl CacheItem<()> for Build {
+    // The build is always requested to find out whether the game was updated
+    fn from_cache(_cache: &Cache, _: &()) -> Option<Build> {
+        None
+    }
+
+    fn to_cache(&self, cache: &Cache) {
+        cache.set_build(self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::Listing;
+    use chrono::TimeZone;
+
+    fn cache() -> Cache {
+        Cache {
+            path: PathBuf::new(),
+            contents: CacheContents::default(),
+            max_age: Duration::days(7),
+            compression: false,
+        }
+    }
+
+    // 2021-11-01 is a Monday
+    fn time(day: u32, hour: u32, minute: u32, second: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2021, 11, day, hour, minute, second)
+            .unwrap()
+    }
+
+    #[test]
+    fn expires_after_its_age() {
+        let cached = CachedItem::new_at(
+            (),
+            Expiry::Age(Duration::minutes(5)),
+            &cache(),
+            time(3, 12, 0, 0),
+        );
+        assert!(!cached.expired_at(time(3, 12, 4, 59)));
+        assert!(cached.expired_at(time(3, 12, 5, 0)));
+    }
+
+    #[test]
+    fn expires_at_the_daily_reset() {
+        let cached = CachedItem::new_at((), Expiry::DailyReset, &cache(), time(3, 23, 59, 58));
+        assert!(!cached.expired_at(time(3, 23, 59, 59)));
+        assert!(cached.expired_at(time(4, 0, 0, 0)));
+        assert!(cached.expired_at(time(4, 0, 0, 1)));
+
+        // Fetched right at the reset, it's kept until the next one
+        let cached = CachedItem::new_at((), Expiry::DailyReset, &cache(), time(4, 0, 0, 0));
+        assert!(!cached.expired_at(time(4, 0, 0, 1)));
+        assert!(!cached.expired_at(time(4, 23, 59, 59)));
+        assert!(cached.expired_at(time(5, 0, 0, 0)));
+    }
+
+    #[test]
+    fn expires_at_the_weekly_reset() {
+        let cached = CachedItem::new_at((), Expiry::WeeklyReset, &cache(), time(1, 7, 29, 0));
+        assert!(!cached.expired_at(time(1, 7, 29, 59)));
+        assert!(cached.expired_at(time(1, 7, 30, 0)));
+
+        let cached = CachedItem::new_at((), Expiry::WeeklyReset, &cache(), time(1, 7, 30, 0));
+        assert!(!cached.expired_at(time(1, 7, 30, 1)));
+        // The daily reset in between doesn't matter
+        assert!(!cached.expired_at(time(2, 0, 0, 1)));
+        assert!(!cached.expired_at(time(8, 7, 29, 59)));
+        assert!(cached.expired_at(time(8, 7, 30, 0)));
+    }
+
+    #[test]
+    fn build_expiry_is_kept_for_the_max_age() {
+        let cache = cache();
+        cache.set_build(100);
+        let cached = CachedItem::new_at((), Expiry::Build, &cache, time(1, 0, 0, 0));
+        assert_eq!(cached.build, Some(100));
+        assert!(!cached.expired_at(time(7, 23, 59, 59)));
+        assert!(cached.expired_at(time(8, 0, 0, 0)));
+    }
+
+    // Achievement IDs expire when the game is updated, dailies at the daily reset, item prices
+    // after a few minutes, and account progress after a minute
+    fn cache_game_and_account(cache: &Cache) {
+        AllAchievementIDs(vec![1, 2, 3]).to_cache(cache);
+        Dailies {
+            pve: vec![],
+            pvp: vec![],
+            wvw: vec![],
+            fractals: vec![],
+            special: vec![],
+        }
+        .to_cache(cache);
+        ItemPrice {
+            id: 1,
+            whitelisted: true,
+            buys: Listing {
+                quantity: 1,
+                unit_price: 100,
+            },
+            sells: Listing {
+                quantity: 1,
+                unit_price: 120,
+            },
+        }
+        .to_cache(cache);
+        AllAccountAchievements(HashSet::new()).to_cache(cache);
+    }
+
+    // Whether the achievement IDs, dailies and item price are each still cached
+    fn game_cached(cache: &Cache) -> [bool; 3] {
+        [
+            AllAchievementIDs::from_cache(cache, &()).is_some(),
+            Dailies::from_cache(cache, &()).is_some(),
+            ItemPrice::from_cache(cache, &1).is_some(),
+        ]
+    }
+
+    fn progress_cached(cache: &Cache) -> bool {
+        AllAccountAchievements::from_cache(cache, &()).is_some()
+    }
+
+    #[test]
+    fn build_change_expires_everything_but_the_account() {
+        let cache = cache();
+        cache.set_build(100);
+        cache_game_and_account(&cache);
+
+        cache.set_build(101);
+        assert_eq!(cache.build(), Some(101));
+        assert_eq!(game_cached(&cache), [false; 3]);
+        assert!(progress_cached(&cache));
+
+        // Fetched under the new build, so it's kept
+        cache_game_and_account(&cache);
+        assert_eq!(game_cached(&cache), [true; 3]);
+    }
+
+    #[test]
+    fn same_build_expires_nothing() {
+        let cache = cache();
+        cache.set_build(100);
+        cache_game_and_account(&cache);
+
+        cache.set_build(100);
+        assert_eq!(game_cached(&cache), [true; 3]);
+        assert!(progress_cached(&cache));
+    }
+
+    #[test]
+    fn first_build_expires_what_was_fetched_before_it_was_known() {
+        let cache = cache();
+        cache_game_and_account(&cache);
+
+        cache.set_build(100);
+        assert_eq!(game_cached(&cache), [false; 3]);
+        assert!(progress_cached(&cache));
+    }
+
+    #[test]
+    fn missing_paths_are_remembered() {
+        let cache = cache();
+        assert!(!cache.is_missing("v2/titles?ids=1"));
+
+        cache.set_missing("v2/titles?ids=1".to_string());
+        assert!(cache.is_missing("v2/titles?ids=1"));
+        assert!(!cache.is_missing("v2/titles?ids=2"));
+    }
+
+    #[test]
+    fn missing_paths_are_forgotten_after_a_game_update() {
+        let cache = cache();
+        cache.set_build(100);
+        cache.set_missing("v2/titles?ids=1".to_string());
+
+        cache.set_build(101);
+        assert!(!cache.is_missing("v2/titles?ids=1"));
+    }
+
+    #[test]
+    fn missing_paths_are_forgotten_after_the_max_age() {
+        let cache = Cache {
+            max_age: Duration::zero(),
+            ..cache()
+        };
+        cache.set_missing("v2/titles?ids=1".to_string());
+        assert!(!cache.is_missing("v2/titles?ids=1"));
+    }
+}
@@ -0,0 +1,380 @@
+//! Encoding and decoding of the chat links the game uses to share things in chat
+//!
+//! A chat link is a base64 encoded byte string wrapped in `[&` and `]`, for example
+//! `[&BDAEAAA=]`. The first byte is the kind of thing that is linked, followed by its ID and any
+//! extra data. Numbers are stored little-endian.
+
+use std::{convert::TryFrom, fmt, str::FromStr};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The text isn't wrapped in `[&` and `]`
+    NotAChatLink,
+    InvalidBase64,
+    UnknownKind(u8),
+    TooShort,
+    /// The ID is too large to fit in the link
+    IdOutOfRange(usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotAChatLink => write!(f, "not a chat link"),
+            Error::InvalidBase64 => write!(f, "invalid chat link code"),
+            Error::UnknownKind(header) => write!(f, "unsupported chat link type {:#04x}", header),
+            Error::TooShort => write!(f, "chat link is incomplete"),
+            Error::IdOutOfRange(id) => write!(f, "{} is too large for a chat link", id),
+        }
+    }
+}
+
+const COINS: u8 = 0x01;
+const ITEM: u8 = 0x02;
+const MAP: u8 = 0x04;
+const SKILL: u8 = 0x06;
+const TRAIT: u8 = 0x07;
+const RECIPE: u8 = 0x09;
+const SKIN: u8 = 0x0A;
+const OUTFIT: u8 = 0x0B;
+const BUILD_TEMPLATE: u8 = 0x0D;
+
+const ITEM_HAS_SKIN: u8 = 0x80;
+const ITEM_HAS_UPGRADE: u8 = 0x40;
+const ITEM_HAS_SECOND_UPGRADE: u8 = 0x20;
+
+/// Something that can be linked in chat
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ChatLink {
+    /// An amount of copper
+    Coins(usize),
+    Item {
+        id: usize,
+        count: u8,
+        skin: Option<usize>,
+        /// The upgrade components, such as runes or sigils, in the item's two upgrade slots
+        upgrades: [Option<usize>; 2],
+    },
+    /// A waypoint, point of interest or vista
+    Map(usize),
+    Skill(usize),
+    Trait(usize),
+    Recipe(usize),
+    Skin(usize),
+    Outfit(usize),
+    /// The specializations, skills and pets of a build. The data is kept as it is.
+    BuildTemplate(Vec<u8>),
+}
+
+impl ChatLink {
+    pub fn decode(link: &str) -> Result<Self> {
+        let code = link
+            .trim()
+            .strip_prefix("[&")
+            .and_then(|code| code.strip_suffix(']'))
+            .ok_or(Error::NotAChatLink)?;
+        let bytes = base64::decode(code).map_err(|_| Error::InvalidBase64)?;
+        let (header, data) = bytes.split_first().ok_or(Error::TooShort)?;
+
+        match *header {
+            COINS => read_u32(data, 0).map(ChatLink::Coins),
+            ITEM => Self::decode_item(data),
+            MAP => read_u32(data, 0).map(ChatLink::Map),
+            SKILL => read_u32(data, 0).map(ChatLink::Skill),
+            TRAIT => read_u32(data, 0).map(ChatLink::Trait),
+            RECIPE => read_u32(data, 0).map(ChatLink::Recipe),
+            SKIN => read_u32(data, 0).map(ChatLink::Skin),
+            OUTFIT => read_u32(data, 0).map(ChatLink::Outfit),
+            BUILD_TEMPLATE if !data.is_empty() => Ok(ChatLink::BuildTemplate(data.to_vec())),
+            BUILD_TEMPLATE => Err(Error::TooShort),
+            header => Err(Error::UnknownKind(header)),
+        }
+    }
+
+    // An item is a count, followed by a 3 byte ID and a byte of flags saying which of the skin
+    // and upgrades follow it
+    fn decode_item(data: &[u8]) -> Result<Self> {
+        let count = *data.first().ok_or(Error::TooShort)?;
+        let id = read_u24(data, 1)?;
+        let flags = *data.get(4).ok_or(Error::TooShort)?;
+
+        let mut offset = 5;
+        let mut read_next = |flag: u8| -> Result<Option<usize>> {
+            if flags & flag == 0 {
+                return Ok(None);
+            }
+            let value = read_u32(data, offset)?;
+            offset += 4;
+            Ok(Some(value))
+        };
+
+        let skin = read_next(ITEM_HAS_SKIN)?;
+        let upgrades = [
+            read_next(ITEM_HAS_UPGRADE)?,
+            read_next(ITEM_HAS_SECOND_UPGRADE)?,
+        ];
+
+        Ok(ChatLink::Item {
+            id,
+            count,
+            skin,
+            upgrades,
+        })
+    }
+
+    /// The chat link code for this, such as `[&BDAEAAA=]`
+    ///
+    /// Fails if an ID doesn't fit in the link. Item IDs have 3 bytes, everything else has 4.
+    pub fn encode(&self) -> Result<String> {
+        let mut bytes = vec![];
+        match self {
+            ChatLink::Coins(copper) => write_id(&mut bytes, COINS, *copper)?,
+            ChatLink::Item {
+                id,
+                count,
+                skin,
+                upgrades,
+            } => {
+                let mut flags = 0;
+                if skin.is_some() {
+                    flags |= ITEM_HAS_SKIN;
+                }
+                if upgrades[0].is_some() {
+                    flags |= ITEM_HAS_UPGRADE;
+                }
+                if upgrades[1].is_some() {
+                    flags |= ITEM_HAS_SECOND_UPGRADE;
+                }
+
+                bytes.push(ITEM);
+                bytes.push(*count);
+                bytes.extend_from_slice(&to_u24(*id)?);
+                bytes.push(flags);
+                for extra in skin.iter().chain(upgrades.iter().flatten()) {
+                    bytes.extend_from_slice(&to_u32(*extra)?);
+                }
+            }
+            ChatLink::Map(id) => write_id(&mut bytes, MAP, *id)?,
+            ChatLink::Skill(id) => write_id(&mut bytes, SKILL, *id)?,
+            ChatLink::Trait(id) => write_id(&mut bytes, TRAIT, *id)?,
+            ChatLink::Recipe(id) => write_id(&mut bytes, RECIPE, *id)?,
+            ChatLink::Skin(id) => write_id(&mut bytes, SKIN, *id)?,
+            ChatLink::Outfit(id) => write_id(&mut bytes, OUTFIT, *id)?,
+            ChatLink::BuildTemplate(data) => {
+                bytes.push(BUILD_TEMPLATE);
+                bytes.extend_from_slice(data);
+            }
+        }
+        Ok(format!("[&{}]", base64::encode(bytes)))
+    }
+
+    /// A short description of what the link points to, such as "Skill 743"
+    pub fn describe(&self) -> String {
+        match self {
+            ChatLink::Coins(copper) => format!("{} copper", copper),
+            ChatLink::Item {
+                id,
+                count,
+                skin,
+                upgrades,
+            } => {
+                let mut description = format!("{}x Item {}", count, id);
+                if let Some(skin) = skin {
+                    description.push_str(&format!(" with skin {}", skin));
+                }
+                if upgrades.iter().any(Option::is_some) {
+                    description.push_str(&format!(
+                        " with upgrades {}",
+                        upgrades
+                            .iter()
+                            .flatten()
+                            .map(|upgrade| upgrade.to_string())
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ));
+                }
+                description
+            }
+            ChatLink::Map(id) => format!("Waypoint/POI {}", id),
+            ChatLink::Skill(id) => format!("Skill {}", id),
+            ChatLink::Trait(id) => format!("Trait {}", id),
+            ChatLink::Recipe(id) => format!("Recipe {}", id),
+            ChatLink::Skin(id) => format!("Skin {}", id),
+            ChatLink::Outfit(id) => format!("Outfit {}", id),
+            ChatLink::BuildTemplate(_) => "Build template".to_string(),
+        }
+    }
+}
+
+impl FromStr for ChatLink {
+    type Err = Error;
+
+    fn from_str(link: &str) -> Result<Self> {
+        Self::decode(link)
+    }
+}
+
+fn write_id(bytes: &mut Vec<u8>, header: u8, id: usize) -> Result<()> {
+    bytes.push(header);
+    bytes.extend_from_slice(&to_u32(id)?);
+    Ok(())
+}
+
+fn to_u24(id: usize) -> Result<[u8; 3]> {
+    match to_u32(id)? {
+        [a, b, c, 0] => Ok([a, b, c]),
+        _ => Err(Error::IdOutOfRange(id)),
+    }
+}
+
+fn to_u32(id: usize) -> Result<[u8; 4]> {
+    u32::try_from(id)
+        .map(u32::to_le_bytes)
+        .map_err(|_| Error::IdOutOfRange(id))
+}
+
+fn read_u24(data: &[u8], offset: usize) -> Result<usize> {
+    match data.get(offset..offset + 3) {
+        Some(&[a, b, c]) => Ok(u32::from_le_bytes([a, b, c, 0]) as usize),
+        _ => Err(Error::TooShort),
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<usize> {
+    match data.get(offset..offset + 4) {
+        Some(&[a, b, c, d]) => Ok(u32::from_le_bytes([a, b, c, d]) as usize),
+        _ => Err(Error::TooShort),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_round_trip(link: &str, expected: ChatLink) {
+        let decoded = ChatLink::decode(link).unwrap();
+        assert_eq!(decoded, expected);
+        assert_eq!(decoded.encode().unwrap(), link);
+    }
+
+    #[test]
+    fn coins() {
+        assert_round_trip("[&AdsnAAA=]", ChatLink::Coins(10203));
+    }
+
+    #[test]
+    fn item() {
+        assert_round_trip(
+            "[&AgH1WQAA]",
+            ChatLink::Item {
+                id: 23029,
+                count: 1,
+                skin: None,
+                upgrades: [None, None],
+            },
+        );
+    }
+
+    #[test]
+    fn item_with_skin_and_upgrades() {
+        let link = ChatLink::Item {
+            id: 46762,
+            count: 1,
+            skin: Some(3709),
+            upgrades: [Some(24575), Some(24615)],
+        };
+        assert_eq!(ChatLink::decode(&link.encode().unwrap()), Ok(link));
+    }
+
+    #[test]
+    fn item_with_one_upgrade() {
+        let link = ChatLink::Item {
+            id: 46762,
+            count: 250,
+            skin: None,
+            upgrades: [Some(24575), None],
+        };
+        assert_eq!(ChatLink::decode(&link.encode().unwrap()), Ok(link));
+    }
+
+    #[test]
+    fn item_with_only_second_upgrade() {
+        let link = ChatLink::Item {
+            id: 46762,
+            count: 1,
+            skin: None,
+            upgrades: [None, Some(24615)],
+        };
+        assert_eq!(ChatLink::decode(&link.encode().unwrap()), Ok(link));
+    }
+
+    #[test]
+    fn ids_out_of_range() {
+        let too_large = u32::MAX as usize + 1;
+        assert_eq!(
+            ChatLink::Skill(too_large).encode(),
+            Err(Error::IdOutOfRange(too_large))
+        );
+        assert_eq!(
+            ChatLink::Item {
+                id: 0x0100_0000,
+                count: 1,
+                skin: None,
+                upgrades: [None, None],
+            }
+            .encode(),
+            Err(Error::IdOutOfRange(0x0100_0000))
+        );
+        assert_eq!(
+            ChatLink::Item {
+                id: 0x00FF_FFFF,
+                count: 1,
+                skin: None,
+                upgrades: [Some(too_large), None],
+            }
+            .encode(),
+            Err(Error::IdOutOfRange(too_large))
+        );
+    }
+
+    #[test]
+    fn waypoint() {
+        assert_round_trip("[&BDAEAAA=]", ChatLink::Map(1072));
+    }
+
+    #[test]
+    fn skill() {
+        assert_round_trip("[&BucCAAA=]", ChatLink::Skill(743));
+    }
+
+    #[test]
+    fn ids_round_trip() {
+        for link in [
+            ChatLink::Trait(1010),
+            ChatLink::Recipe(7),
+            ChatLink::Skin(10),
+            ChatLink::Outfit(27),
+        ] {
+            assert_eq!(ChatLink::decode(&link.encode().unwrap()), Ok(link));
+        }
+    }
+
+    #[test]
+    fn build_template() {
+        let link = ChatLink::BuildTemplate((1..=44).collect());
+        assert_eq!(ChatLink::decode(&link.encode().unwrap()), Ok(link));
+    }
+
+    #[test]
+    fn invalid_links() {
+        assert_eq!(ChatLink::decode("BDAEAAA="), Err(Error::NotAChatLink));
+        assert_eq!(ChatLink::decode("[&!!!]"), Err(Error::InvalidBase64));
+        assert_eq!(ChatLink::decode("[&BDAE]"), Err(Error::TooShort));
+        assert_eq!(
+            ChatLink::decode("[&AwAAAAA=]"),
+            Err(Error::UnknownKind(0x03))
+        );
+    }
+}
@@ -4,7 +4,7 @@ use chrono::Duration;
 use clap::{App, Arg};
 
 /// Contains all the possible arguments passed from the command line.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Options {
     pub config_path: Option<PathBuf>,
     pub gateway: Option<String>,
@@ -17,22 +17,6 @@ pub struct Options {
     pub starting_tab: Option<usize>,
 }
 
-impl Default for Options {
-    fn default() -> Self {
-        Options {
-            config_path: None,
-            gateway: None,
-            apikey: None,
-            offline: false,
-            verbose: false,
-            cache_path: None,
-            cache_age: None,
-            cache_compression: false,
-            starting_tab: None,
-        }
-    }
-}
-
 impl Options {
     // Automatically grabs, parses, and returns an Options object with all the selected user options
     pub fn new() -> Self {
@@ -40,7 +24,7 @@ impl Options {
         let matches = App::new("Orrient")
             .version("0.1.0")
             .about("Keep track of dailies, achievements, crafting, etc in Guild Wars 2")
-            .args(&vec![
+            .args(&[
                 Arg::with_name("config")
                     .short("c")
                     .long("config")
@@ -120,8 +104,7 @@ impl Options {
         options.cache_compression = matches.is_present("cache-compression");
         options.starting_tab = matches
             .value_of("starting-tab")
-            .map(|starting_tab| starting_tab.parse::<usize>().ok())
-            .flatten();
+            .and_then(|starting_tab| starting_tab.parse::<usize>().ok());
 
         options
     }
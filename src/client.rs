@@ -1,10 +1,22 @@
-use std::{fmt::Debug, hash::Hash, sync::Arc};
+use std::{
+    collections::hash_map::RandomState,
+    fmt::{self, Debug},
+    hash::{BuildHasher, Hash, Hasher},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use reqwest::header::HeaderMap;
+use futures::{stream, Stream, StreamExt};
+use log::debug;
+use reqwest::{header::HeaderMap, StatusCode};
 use serde::de::DeserializeOwned;
+use tokio::sync::Mutex;
 
 use crate::{
-    api::endpoints::Endpoint,
+    api::{
+        endpoints::{Endpoint, MAX_IDS_PER_REQUEST, MAX_PAGE_SIZE},
+        Build, Item,
+    },
     cache::{Cache, CacheItem},
     config::Config,
 };
@@ -16,19 +28,187 @@ pub enum Error {
     MissingApiKey,
     Request(reqwest::Error),
     Json(serde_json::Error),
+    /// The API key is wrong or doesn't have the permissions the endpoint needs
+    InvalidKey(String),
+    /// Too many requests were made. Holds how long the gateway asked to wait, if it did.
+    RateLimited(Option<Duration>),
+    NotFound(String),
+    /// The gateway or the game servers behind it had a problem
+    ServerError(u16, String),
+    /// Only some of the requested IDs exist. Holds the body with the ones that were found.
+    PartialContent(String),
+    /// Any other response that wasn't successful
+    Unexpected(u16, String),
+    /// The endpoint can't be requested this way
+    Unsupported(String),
+}
+
+impl Error {
+    /// Sort out an unsuccessful response from the gateway by its status and error message
+    fn from_response(status: StatusCode, headers: &HeaderMap, body: &str) -> Self {
+        #[derive(Deserialize)]
+        struct GatewayError {
+            text: String,
+        }
+
+        let text = serde_json::from_str::<GatewayError>(body)
+            .map(|error| error.text)
+            .unwrap_or_else(|_| body.to_string());
+        let lowercase = text.to_lowercase();
+        if status == StatusCode::UNAUTHORIZED
+            || status == StatusCode::FORBIDDEN
+            || lowercase.contains("invalid key")
+            || lowercase.contains("invalid access token")
+        {
+            Error::InvalidKey(text)
+        } else if status == StatusCode::NOT_FOUND {
+            Error::NotFound(text)
+        } else if status == StatusCode::TOO_MANY_REQUESTS {
+            // The gateway gives the time to wait in seconds
+            let retry_after = headers
+                .get("Retry-After")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            Error::RateLimited(retry_after)
+        } else if status.is_server_error() {
+            Error::ServerError(status.as_u16(), text)
+        } else {
+            Error::Unexpected(status.as_u16(), text)
+        }
+    }
+
+    /// Whether the request might work if it's tried again
+    fn is_transient(&self) -> bool {
+        match self {
+            Error::RateLimited(_) | Error::ServerError(..) => true,
+            Error::Request(err) => err.is_timeout() || err.is_connect(),
+            _ => false,
+        }
+    }
+
+    /// A short description of what went wrong, to fit in the status bar
+    pub fn summary(&self) -> String {
+        match self {
+            Error::MissingApiKey => "no API key".to_string(),
+            Error::Request(err) if err.is_timeout() => "the gateway took too long".to_string(),
+            Error::Request(_) => "could not reach the gateway".to_string(),
+            Error::Json(_) => "unexpected response".to_string(),
+            Error::InvalidKey(_) => "invalid API key".to_string(),
+            Error::RateLimited(_) => "too many requests".to_string(),
+            Error::NotFound(_) => "not found".to_string(),
+            Error::ServerError(status, _) => format!("server error {}", status),
+            Error::PartialContent(_) => "some IDs were not found".to_string(),
+            Error::Unexpected(status, _) => format!("unexpected status {}", status),
+            Error::Unsupported(_) => "unsupported request".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingApiKey => write!(f, "no API key was provided"),
+            Error::Request(err) => write!(f, "request failed: {}", err),
+            Error::Json(err) => write!(f, "unexpected response: {}", err),
+            Error::InvalidKey(text) => write!(f, "invalid API key: {}", text),
+            Error::RateLimited(_) => write!(f, "too many requests"),
+            Error::NotFound(text) => write!(f, "not found: {}", text),
+            Error::ServerError(status, text) => write!(f, "server error {}: {}", status, text),
+            Error::PartialContent(_) => write!(f, "only some of the requested IDs were found"),
+            Error::Unexpected(status, text) => write!(f, "unexpected status {}: {}", status, text),
+            Error::Unsupported(text) => write!(f, "unsupported request: {}", text),
+        }
+    }
+}
+
+/// The response to a request for many items at once
+#[derive(Debug)]
+pub struct ManyResponse<E, P> {
+    /// The items that exist
+    pub found: Vec<E>,
+    /// The parameters the gateway had nothing for
+    pub missing: Vec<P>,
+    /// The parameters that couldn't be requested because of an error
+    pub failed: Vec<P>,
+    /// What went wrong with the requests for the failed parameters
+    pub errors: Vec<Error>,
+}
+
+impl<E, P: Clone> ManyResponse<E, P> {
+    /// Add what was found and missing in the request for a chunk of `ids`. If it failed, all of
+    /// them failed.
+    fn add_chunk(&mut self, ids: &[&P], chunk: Result<(Vec<E>, Vec<P>)>) {
+        match chunk {
+            Ok((found, missing)) => {
+                self.found.extend(found);
+                self.missing.extend(missing);
+            }
+            Err(err) => {
+                self.failed.extend(ids.iter().map(|id| (*id).clone()));
+                self.errors.push(err);
+            }
+        }
+    }
+}
+
+/// A single page of an [Endpoint]
+#[derive(Debug)]
+pub struct Page<E> {
+    pub items: Vec<E>,
+    /// Which page this is, starting from 0
+    pub page: usize,
+    /// How many pages there are at this page size
+    pub page_total: usize,
+    /// How many items there are across every page
+    pub result_total: usize,
+}
+
+impl<E> Page<E> {
+    /// Read how many pages and items there are from the headers of the response. Without them,
+    /// this is assumed to be the last page.
+    fn from_response(items: Vec<E>, page: usize, page_size: usize, headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<usize>().ok())
+        };
+        Self {
+            page,
+            page_total: header("X-Page-Total").unwrap_or(page + 1),
+            result_total: header("X-Result-Total").unwrap_or(page * page_size + items.len()),
+            items,
+        }
+    }
+
+    /// The page after this one, if there is one
+    pub fn next_page(&self) -> Option<usize> {
+        Some(self.page + 1).filter(|next| *next < self.page_total)
+    }
 }
 
 /// Tries to make requests to the [Cache] before it reachs out to the gateway
 pub struct CachedClient {
     client: GW2Client,
     cache: Arc<Cache>,
+    max_concurrent_requests: usize,
 }
 
 impl CachedClient {
     pub fn new(config: Config) -> Result<Self> {
         let client = GW2Client::new(&config);
         let cache = Arc::new(Cache::load(&config));
-        Ok(CachedClient { client, cache })
+        Ok(CachedClient {
+            client,
+            cache,
+            max_concurrent_requests: config.max_concurrent_requests.max(1),
+        })
+    }
+
+    /// How many requests can be made at the same time when loading a lot of data
+    pub fn max_concurrent_requests(&self) -> usize {
+        self.max_concurrent_requests
     }
 
     /// Tell cache to commit to disk
@@ -52,43 +232,190 @@ impl CachedClient {
         }
     }
 
-    /// Make a cached request for a list of [Endpoint]'s from a list of parameters
-    pub async fn request_many<E, P>(&self, params: &[P]) -> Result<Vec<E>>
+    /// Make a cached request for a single [Endpoint] from a parameter
+    pub async fn request_with_param<E, P>(&self, param: &P) -> Result<E>
+    where
+        E: Endpoint<P> + CacheItem<P> + DeserializeOwned + Debug,
+    {
+        match E::from_cache(&self.cache, param) {
+            Some(cached) => Ok(cached),
+            None => {
+                let response = self.client.request_with_param::<E, P>(param).await?;
+                response.to_cache(&self.cache);
+                Ok(response)
+            }
+        }
+    }
+
+    /// Request the current game build. Returns the new build if the game was updated since it was
+    /// last checked.
+    pub async fn check_build(&self) -> Result<Option<usize>> {
+        let previous = self.cache.build();
+        let build = self.request::<Build>().await?;
+        Ok(Some(build.id).filter(|build| previous.is_some_and(|previous| previous != *build)))
+    }
+
+    /// Make a cached request for every item of an [Endpoint] that can be requested all at once.
+    /// Each item is cached on its own, so it can also be found by [CachedClient::request_many].
+    pub async fn request_all<E, P>(&self) -> Result<Vec<E>>
+    where
+        E: Endpoint<P> + CacheItem<P> + DeserializeOwned + Debug,
+    {
+        let path = E::get_all_path().ok_or_else(|| {
+            Error::Unsupported(format!(
+                "{} can't be requested all at once",
+                std::any::type_name::<E>()
+            ))
+        })?;
+        if self.cache.is_complete(&path) {
+            return Ok(E::all_from_cache(&self.cache));
+        }
+
+        let items = self.client.request_all::<E, P>().await?;
+        for item in &items {
+            item.to_cache(&self.cache);
+        }
+        self.cache.set_complete(path, E::expiry(&self.cache));
+        Ok(items)
+    }
+
+    /// Request every page of a paged [Endpoint], one after another. Each page is yielded as soon
+    /// as it arrives and its items are cached. The stream ends after the last page or the first
+    /// error. Once the last page arrives, [CachedClient::has_every_page] is true until the items
+    /// expire.
+    pub fn request_pages<E, P>(&self, page_size: usize) -> impl Stream<Item = Result<Page<E>>> + '_
+    where
+        E: Endpoint<P> + CacheItem<P> + DeserializeOwned + Debug,
+    {
+        stream::unfold(Some(0), move |page| async move {
+            let page = page?;
+            match self.client.request_page::<E, P>(page, page_size).await {
+                Ok(response) => {
+                    for item in &response.items {
+                        item.to_cache(&self.cache);
+                    }
+                    let next = response.next_page();
+                    if let (None, Some(path)) = (next, E::PAGED_PATH) {
+                        self.cache
+                            .set_complete(path.to_string(), E::expiry(&self.cache));
+                    }
+                    Some((Ok(response), next))
+                }
+                Err(err) => Some((Err(err), None)),
+            }
+        })
+    }
+
+    /// Whether every page of a paged [Endpoint] was requested by [CachedClient::request_pages]
+    /// and is still cached
+    pub fn has_every_page<E, P>(&self) -> bool
+    where
+        E: Endpoint<P>,
+    {
+        E::PAGED_PATH.is_some_and(|path| self.cache.is_complete(path))
+    }
+
+    /// Find items in the cache by name
+    pub fn search_cached_items(&self, name: &str) -> Vec<Item> {
+        Item::search_cache(&self.cache, name)
+    }
+
+    /// Make a cached request for a list of [Endpoint]'s from a list of parameters. Parameters
+    /// the gateway has nothing for are returned separately and aren't requested again for a while.
+    /// If some of the requests fail, everything else is still returned.
+    pub async fn request_many<E, P>(&self, params: &[P]) -> ManyResponse<E, P>
     where
         E: Endpoint<P> + CacheItem<P> + DeserializeOwned + Debug + Send,
-        P: Hash + Eq + Debug,
+        P: Hash + Eq + Debug + Clone + Sync,
     {
-        // Sort out which items are already cached and which need to be fetched from the gateway
+        self.request_many_with_progress(params, |_, _| {}).await
+    }
+
+    /// The same as [CachedClient::request_many], but `progress` is called with how many of the
+    /// gateway requests are done and how many there are in total each time one finishes
+    pub async fn request_many_with_progress<E, P, F>(
+        &self,
+        params: &[P],
+        progress: F,
+    ) -> ManyResponse<E, P>
+    where
+        E: Endpoint<P> + CacheItem<P> + DeserializeOwned + Debug + Send,
+        P: Hash + Eq + Debug + Clone + Sync,
+        F: Fn(usize, usize),
+    {
+        // Sort out which items are already cached, known to be missing, or need to be fetched
+        // from the gateway
         let mut request_items: Vec<&P> = vec![];
+        let mut missing: Vec<P> = vec![];
         let cached_items: Vec<E> = params
             .iter()
-            .map(|param| {
+            .filter_map(|param| {
                 E::from_cache(&self.cache, param).or_else(|| {
-                    request_items.push(param);
+                    if self.cache.is_missing(&E::get_path(vec![param])) {
+                        missing.push(param.clone());
+                    } else {
+                        request_items.push(param);
+                    }
                     None
                 })
             })
-            .flatten()
             .collect();
 
-        // If everything  was cached then we don't need to continue to make the gateway request
-        if request_items.is_empty() {
-            return Ok(cached_items);
+        // Something wasn't in cache so make gateway requests, split up so no single request asks
+        // for more IDs than the gateway allows
+        let mut response = ManyResponse {
+            found: cached_items,
+            missing,
+            failed: vec![],
+            errors: vec![],
+        };
+        let total_chunks = request_items.chunks(MAX_IDS_PER_REQUEST).count();
+        let mut chunks = stream::iter(request_items.chunks(MAX_IDS_PER_REQUEST))
+            .map(|ids| async move { (ids, self.request_chunk::<E, P>(ids).await) })
+            .buffer_unordered(self.max_concurrent_requests);
+        let mut done_chunks = 0;
+        while let Some((ids, chunk)) = chunks.next().await {
+            response.add_chunk(ids, chunk);
+            done_chunks += 1;
+            progress(done_chunks, total_chunks);
+        }
+
+        response
+    }
+
+    /// Request a list of items that weren't cached from the gateway, returning the ones that were
+    /// found and the ones that weren't
+    async fn request_chunk<E, P>(&self, params: &[&P]) -> Result<(Vec<E>, Vec<P>)>
+    where
+        E: Endpoint<P> + CacheItem<P> + DeserializeOwned + Debug + Send,
+        P: Hash + Eq + Debug + Clone + Sync,
+    {
+        let remote_items = match self
+            .client
+            .request_with_params::<E, P>(params.to_vec())
+            .await
+        {
+            Ok(remote_items) => remote_items,
+            // None of the IDs exist
+            Err(Error::NotFound(_)) => vec![],
+            Err(e) => return Err(e),
+        };
+        for item in &remote_items {
+            item.to_cache(&self.cache);
         }
 
-        // Something wasn't in cache so make a gateway request
-        match self.client.request_with_params::<E, P>(request_items).await {
-            Ok(remote_items) => {
-                for item in &remote_items {
-                    item.to_cache(&self.cache);
+        // Anything that was found is in the cache now, so whatever isn't was missing
+        let mut missing = vec![];
+        if remote_items.len() < params.len() {
+            for param in params {
+                if E::from_cache(&self.cache, param).is_none() {
+                    self.cache.set_missing(E::get_path(vec![param]));
+                    missing.push((*param).clone());
                 }
-                Ok(cached_items
-                    .into_iter()
-                    .chain(remote_items.into_iter())
-                    .collect::<Vec<E>>())
             }
-            Err(e) => Err(e),
         }
+
+        Ok((remote_items, missing))
     }
 }
 
@@ -97,6 +424,7 @@ pub struct GW2Client {
     client: reqwest::Client,
     gateway: String,
     apikey: String,
+    rate_limiter: RateLimiter,
 }
 
 impl GW2Client {
@@ -105,6 +433,7 @@ impl GW2Client {
             client: reqwest::Client::new(),
             gateway: config.gateway.clone(),
             apikey: config.apikey.to_owned(),
+            rate_limiter: RateLimiter::new(RATE_LIMIT_BURST, RATE_LIMIT_PER_SECOND),
         }
     }
 
@@ -113,19 +442,17 @@ impl GW2Client {
     where
         E: Endpoint<()> + CacheItem<()> + DeserializeOwned,
     {
-        let request_builder = self
-            .client
-            .get(format!("{}/{}", self.gateway, E::get_path(vec![&()])))
-            .headers(self.get_headers::<E, ()>()?);
+        self.get::<E>(E::get_path(vec![&()]), self.get_headers::<E, ()>()?)
+            .await
+    }
 
-        let response: reqwest::Response = request_builder.send().await.map_err(Error::Request)?;
-        match response.text().await {
-            Ok(text) => match serde_json::from_str::<E>(text.as_str()) {
-                Ok(endpoint) => Ok(endpoint),
-                Err(err) => Err(Error::Json(err)),
-            },
-            Err(err) => Err(Error::Request(err)),
-        }
+    /// Make an uncached request for a single [Endpoint] from a parameter
+    pub async fn request_with_param<E, P>(&self, param: &P) -> Result<E>
+    where
+        E: Endpoint<P> + DeserializeOwned,
+    {
+        self.get::<E>(E::get_path(vec![param]), self.get_headers::<E, P>()?)
+            .await
     }
 
     /// Make an uncached request for a list of [Endpoint]'s from a list of parameters
@@ -133,18 +460,105 @@ impl GW2Client {
     where
         E: Endpoint<P> + DeserializeOwned,
     {
+        match self
+            .get::<Vec<E>>(E::get_path(params), self.get_headers::<E, P>()?)
+            .await
+        {
+            // Some of the IDs don't exist, but the rest can still be used
+            Err(Error::PartialContent(body)) => serde_json::from_str(&body).map_err(Error::Json),
+            result => result,
+        }
+    }
+
+    /// Make an uncached request for every item of an [Endpoint] at once
+    pub async fn request_all<E, P>(&self) -> Result<Vec<E>>
+    where
+        E: Endpoint<P> + DeserializeOwned,
+    {
+        let path = E::get_all_path().ok_or_else(|| {
+            Error::Unsupported(format!(
+                "{} can't be requested all at once",
+                std::any::type_name::<E>()
+            ))
+        })?;
+        self.get::<Vec<E>>(path, self.get_headers::<E, P>()?).await
+    }
+
+    /// Make an uncached request for a single page of an [Endpoint]. The page size is limited to
+    /// what the gateway allows.
+    pub async fn request_page<E, P>(&self, page: usize, page_size: usize) -> Result<Page<E>>
+    where
+        E: Endpoint<P> + DeserializeOwned,
+    {
+        let page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+        let path = E::get_page_path(page, page_size).ok_or_else(|| {
+            Error::Unsupported(format!("{} can't be paged", std::any::type_name::<E>()))
+        })?;
+        let (response_headers, items) = self
+            .get_with_headers::<Vec<E>>(path, self.get_headers::<E, P>()?)
+            .await?;
+        Ok(Page::from_response(
+            items,
+            page,
+            page_size,
+            &response_headers,
+        ))
+    }
+
+    async fn get<T>(&self, path: String, headers: HeaderMap) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.get_with_headers::<T>(path, headers)
+            .await
+            .map(|(_, body)| body)
+    }
+
+    /// Make a request, trying again with an increasing delay if it fails in a way that might
+    /// not happen again. Returns the headers of the response along with its body.
+    async fn get_with_headers<T>(&self, path: String, headers: HeaderMap) -> Result<(HeaderMap, T)>
+    where
+        T: DeserializeOwned,
+    {
+        let mut attempt = 0;
+        loop {
+            match self.get_once::<T>(&path, headers.clone()).await {
+                Err(err) if err.is_transient() && attempt < MAX_RETRIES => {
+                    let delay = match err {
+                        Error::RateLimited(Some(retry_after)) => retry_after,
+                        _ => backoff(attempt),
+                    };
+                    debug!("Retrying {} in {:?}: {}", path, delay, err);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn get_once<T>(&self, path: &str, headers: HeaderMap) -> Result<(HeaderMap, T)>
+    where
+        T: DeserializeOwned,
+    {
+        self.rate_limiter.acquire().await;
         let request_builder = self
             .client
-            .get(format!("{}/{}", self.gateway, E::get_path(params)))
-            .headers(self.get_headers::<E, P>()?);
+            .get(format!("{}/{}", self.gateway, path))
+            .headers(headers);
 
         let response: reqwest::Response = request_builder.send().await.map_err(Error::Request)?;
-        match response.text().await {
-            Ok(text) => match serde_json::from_str::<Vec<E>>(text.as_str()) {
-                Ok(endpoint) => Ok(endpoint),
-                Err(err) => Err(Error::Json(err)),
-            },
-            Err(err) => Err(Error::Request(err)),
+        let status = response.status();
+        let response_headers = response.headers().clone();
+        let text = response.text().await.map_err(Error::Request)?;
+        if status == StatusCode::PARTIAL_CONTENT {
+            Err(Error::PartialContent(text))
+        } else if status.is_success() {
+            serde_json::from_str::<T>(text.as_str())
+                .map(|body| (response_headers, body))
+                .map_err(Error::Json)
+        } else {
+            Err(Error::from_response(status, &response_headers, &text))
         }
     }
 
@@ -171,3 +585,568 @@ impl GW2Client {
         Ok(headermap)
     }
 }
+
+/// How many times to try a request again before giving up
+const MAX_RETRIES: u32 = 4;
+
+/// The delay before trying again grows with each attempt: 0.5s, 1s, 2s, 4s. Half of it is random
+/// so many failed requests don't all try again at the same moment.
+fn backoff(attempt: u32) -> Duration {
+    let delay = Duration::from_millis(500) * 2u32.pow(attempt);
+    // Each RandomState is seeded randomly, so hashing with a new one is a cheap source of
+    // randomness without depending on the rand crate for this alone
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(attempt);
+    let jitter = (hasher.finish() % 1000) as f64 / 1000.0;
+    delay / 2 + delay.mul_f64(jitter / 2.0)
+}
+
+/// The gateway allows bursts of up to 300 requests, refilled at 5 requests per second
+const RATE_LIMIT_BURST: f64 = 300.0;
+const RATE_LIMIT_PER_SECOND: f64 = 5.0;
+
+/// A token bucket to keep requests within the rate limit of the gateway
+struct RateLimiter {
+    capacity: f64,
+    per_second: f64,
+    /// The tokens left and when they were last refilled
+    bucket: Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+    fn new(capacity: f64, per_second: f64) -> Self {
+        Self {
+            capacity,
+            per_second,
+            bucket: Mutex::new((capacity, Instant::now())),
+        }
+    }
+
+    /// Wait until a request can be made
+    async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().await;
+                let (tokens, refilled_at) = *bucket;
+                let now = Instant::now();
+                let tokens = (tokens
+                    + now.duration_since(refilled_at).as_secs_f64() * self.per_second)
+                    .min(self.capacity);
+                if tokens >= 1.0 {
+                    *bucket = (tokens - 1.0, now);
+                    return;
+                }
+                *bucket = (tokens, now);
+                Duration::from_secs_f64((1.0 - tokens) / self.per_second)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{Achievement, AllAchievementIDs, Title};
+    use futures::StreamExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    type Requests = Arc<std::sync::Mutex<Vec<String>>>;
+
+    /// Serve a gateway on a local port that answers each request with the status and body from
+    /// `respond`. Returns its address and the paths it was asked for.
+    async fn fake_gateway<F>(respond: F) -> (String, Requests)
+    where
+        F: Fn(&str) -> (u16, String) + Send + Sync + 'static,
+    {
+        fake_gateway_with_headers(move |path| {
+            let (status, body) = respond(path);
+            (status, vec![], body)
+        })
+        .await
+    }
+
+    /// The same as [fake_gateway], but `respond` also gives the headers to send with each response
+    async fn fake_gateway_with_headers<F>(respond: F) -> (String, Requests)
+    where
+        F: Fn(&str) -> (u16, Vec<(&'static str, String)>, String) + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let gateway = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Requests::default();
+        let respond = Arc::new(respond);
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let respond = respond.clone();
+                let seen = seen.clone();
+                tokio::spawn(async move {
+                    let mut request = vec![];
+                    let mut buffer = [0; 4096];
+                    while !request.ends_with(b"\r\n\r\n") {
+                        let read = socket.read(&mut buffer).await.unwrap();
+                        if read == 0 {
+                            return;
+                        }
+                        request.extend_from_slice(&buffer[..read]);
+                    }
+                    let request = String::from_utf8_lossy(&request);
+                    let path = request.split_whitespace().nth(1).unwrap().to_string();
+                    let (status, headers, body) = respond(&path);
+                    seen.lock().unwrap().push(path);
+                    let headers = headers
+                        .iter()
+                        .map(|(name, value)| format!("{}: {}\r\n", name, value))
+                        .collect::<String>();
+                    let response = format!(
+                        "HTTP/1.1 {} Fake\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        headers,
+                        body.len(),
+                        body
+                    );
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+        (gateway, requests)
+    }
+
+    /// A client for `gateway` with an empty cache
+    fn client(gateway: &str) -> CachedClient {
+        let config: Config = serde_yaml::from_str(&format!(
+            "apikey: test\ngateway: {}\ncache_path: /nonexistent/orrient-test-cache",
+            gateway
+        ))
+        .unwrap();
+        CachedClient::new(config).unwrap()
+    }
+
+    fn headers(page_total: &str, result_total: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Page-Total", page_total.parse().unwrap());
+        headers.insert("X-Result-Total", result_total.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn page_path() {
+        assert_eq!(
+            Achievement::get_page_path(2, 200),
+            Some("v2/achievements?page=2&page_size=200".to_string())
+        );
+        assert_eq!(Build::get_page_path(0, 200), None);
+    }
+
+    #[test]
+    fn pages_stop_after_the_last() {
+        let page = Page::from_response(vec![1, 2], 0, 2, &headers("3", "5"));
+        assert_eq!(page.page_total, 3);
+        assert_eq!(page.result_total, 5);
+        assert_eq!(page.next_page(), Some(1));
+
+        let page = Page::from_response(vec![3, 4], 1, 2, &headers("3", "5"));
+        assert_eq!(page.next_page(), Some(2));
+
+        let page = Page::from_response(vec![5], 2, 2, &headers("3", "5"));
+        assert_eq!(page.next_page(), None);
+
+        // An empty endpoint has no pages at all
+        let page = Page::<usize>::from_response(vec![], 0, 2, &headers("0", "0"));
+        assert_eq!(page.next_page(), None);
+    }
+
+    #[test]
+    fn page_without_headers_is_the_last() {
+        let page = Page::from_response(vec![1, 2, 3], 0, 200, &HeaderMap::new());
+        assert_eq!(page.page_total, 1);
+        assert_eq!(page.result_total, 3);
+        assert_eq!(page.next_page(), None);
+
+        let page = Page::from_response(vec![1, 2, 3], 2, 200, &HeaderMap::new());
+        assert_eq!(page.page_total, 3);
+        assert_eq!(page.result_total, 403);
+        assert_eq!(page.next_page(), None);
+    }
+
+    #[test]
+    fn page_with_invalid_headers_is_the_last() {
+        let page = Page::from_response(vec![1], 0, 200, &headers("many", ""));
+        assert_eq!(page.page_total, 1);
+        assert_eq!(page.result_total, 1);
+        assert_eq!(page.next_page(), None);
+    }
+
+    // Titles 1 to 5, a page at a time. Like the gateway, asking for a page past the last one is an
+    // error.
+    fn paged_titles(path: &str) -> (u16, Vec<(&'static str, String)>, String) {
+        let query = |name: &str| {
+            path.split(&['?', '&'][..])
+                .find_map(|pair| pair.strip_prefix(name))
+                .and_then(|value| value.parse::<usize>().ok())
+                .unwrap()
+        };
+        let (page, page_size) = (query("page="), query("page_size="));
+        let titles = (1..=5)
+            .skip(page * page_size)
+            .take(page_size)
+            .map(|id| format!(r#"{{"id": {}, "name": "Title {}"}}"#, id, id))
+            .collect::<Vec<String>>();
+        if titles.is_empty() {
+            return (400, vec![], r#"{"text": "page out of range"}"#.to_string());
+        }
+        let headers = vec![
+            (
+                "X-Page-Total",
+                ((5 + page_size - 1) / page_size).to_string(),
+            ),
+            ("X-Result-Total", "5".to_string()),
+        ];
+        (200, headers, format!("[{}]", titles.join(",")))
+    }
+
+    #[tokio::test]
+    async fn pages_are_requested_until_the_page_total() {
+        let (gateway, requests) = fake_gateway_with_headers(paged_titles).await;
+        let client = client(&gateway);
+        assert!(!client.has_every_page::<Title, usize>());
+
+        let pages = client
+            .request_pages::<Title, usize>(2)
+            .collect::<Vec<Result<Page<Title>>>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<Page<Title>>>>()
+            .unwrap();
+        assert_eq!(
+            pages.iter().map(|page| page.page).collect::<Vec<usize>>(),
+            vec![0, 1, 2]
+        );
+        assert!(pages
+            .iter()
+            .all(|page| page.page_total == 3 && page.result_total == 5));
+        assert_eq!(
+            pages
+                .iter()
+                .flat_map(|page| page.items.iter().map(|title| title.id))
+                .collect::<Vec<usize>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![
+                "/v2/titles?page=0&page_size=2",
+                "/v2/titles?page=1&page_size=2",
+                "/v2/titles?page=2&page_size=2",
+            ]
+        );
+
+        // Each page was cached as it arrived
+        let response = client.request_many::<Title, usize>(&[1, 5]).await;
+        assert_eq!(response.found.len(), 2);
+        assert_eq!(requests.lock().unwrap().len(), 3);
+        assert!(client.has_every_page::<Title, usize>());
+    }
+
+    #[tokio::test]
+    async fn pages_stop_at_the_first_error() {
+        let (gateway, requests) = fake_gateway_with_headers(|path| {
+            if path.contains("page=1&") {
+                (400, vec![], r#"{"text": "bad request"}"#.to_string())
+            } else {
+                paged_titles(path)
+            }
+        })
+        .await;
+        let client = client(&gateway);
+
+        let pages = client
+            .request_pages::<Title, usize>(2)
+            .collect::<Vec<Result<Page<Title>>>>()
+            .await;
+        assert!(matches!(
+            pages.as_slice(),
+            [Ok(_), Err(Error::Unexpected(400, _))]
+        ));
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        assert!(!client.has_every_page::<Title, usize>());
+    }
+
+    #[tokio::test]
+    async fn build_change_is_reported_once() {
+        let build = Arc::new(AtomicUsize::new(100));
+        let gateway_build = build.clone();
+        let (gateway, _) = fake_gateway(move |_| {
+            (
+                200,
+                format!(r#"{{"id": {}}}"#, gateway_build.load(Ordering::SeqCst)),
+            )
+        })
+        .await;
+        let client = client(&gateway);
+
+        // The first build seen isn't an update
+        assert_eq!(client.check_build().await.unwrap(), None);
+        assert_eq!(client.check_build().await.unwrap(), None);
+
+        build.store(101, Ordering::SeqCst);
+        assert_eq!(client.check_build().await.unwrap(), Some(101));
+        assert_eq!(client.check_build().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn build_change_expires_achievement_ids() {
+        let build = Arc::new(AtomicUsize::new(100));
+        let gateway_build = build.clone();
+        let (gateway, requests) = fake_gateway(move |path| match path {
+            "/v2/build" => (
+                200,
+                format!(r#"{{"id": {}}}"#, gateway_build.load(Ordering::SeqCst)),
+            ),
+            _ => (200, "[1, 2, 3]".to_string()),
+        })
+        .await;
+        let client = client(&gateway);
+        let ids_requests = || {
+            requests
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|path| *path == "/v2/achievements")
+                .count()
+        };
+
+        client.check_build().await.unwrap();
+        client.request::<AllAchievementIDs>().await.unwrap();
+        client.check_build().await.unwrap();
+        client.request::<AllAchievementIDs>().await.unwrap();
+        assert_eq!(ids_requests(), 1);
+
+        build.store(101, Ordering::SeqCst);
+        client.check_build().await.unwrap();
+        client.request::<AllAchievementIDs>().await.unwrap();
+        assert_eq!(ids_requests(), 2);
+    }
+
+    // Titles 1 to 3 exist, anything else doesn't
+    fn titles(path: &str) -> (u16, String) {
+        let ids = path.split("ids=").nth(1).unwrap_or_default();
+        let found = ids
+            .split(',')
+            .filter_map(|id| id.parse::<usize>().ok())
+            .filter(|id| (1..=3).contains(id))
+            .map(|id| format!(r#"{{"id": {}, "name": "Title {}"}}"#, id, id))
+            .collect::<Vec<String>>();
+        let status = if found.is_empty() {
+            404
+        } else if found.len() < ids.split(',').count() {
+            206
+        } else {
+            200
+        };
+        (status, format!("[{}]", found.join(",")))
+    }
+
+    #[tokio::test]
+    async fn missing_ids_are_not_requested_again() {
+        let (gateway, requests) = fake_gateway(titles).await;
+        let client = client(&gateway);
+
+        let response = client.request_many::<Title, usize>(&[1, 2, 4, 5]).await;
+        assert_eq!(response.found.len(), 2);
+        assert_eq!(response.missing, vec![4, 5]);
+        assert!(response.failed.is_empty());
+
+        // Found IDs come from the cache and missing IDs aren't asked for again
+        let response = client.request_many::<Title, usize>(&[1, 2, 4, 5]).await;
+        assert_eq!(response.found.len(), 2);
+        assert_eq!(response.missing, vec![4, 5]);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        // Only the new ID is requested
+        let response = client.request_many::<Title, usize>(&[3, 4, 6]).await;
+        assert_eq!(
+            response.found,
+            vec![Title {
+                id: 3,
+                name: "Title 3".to_string()
+            }]
+        );
+        assert_eq!(response.missing, vec![4, 6]);
+        assert_eq!(
+            requests.lock().unwrap().last().unwrap(),
+            "/v2/titles?ids=3,6"
+        );
+    }
+
+    #[tokio::test]
+    async fn nothing_found_is_all_missing() {
+        let (gateway, _) = fake_gateway(titles).await;
+        let client = client(&gateway);
+
+        let response = client.request_many::<Title, usize>(&[7, 8]).await;
+        assert!(response.found.is_empty());
+        assert_eq!(response.missing, vec![7, 8]);
+        assert!(response.errors.is_empty());
+    }
+
+    fn empty_response() -> ManyResponse<usize, usize> {
+        ManyResponse {
+            found: vec![],
+            missing: vec![],
+            failed: vec![],
+            errors: vec![],
+        }
+    }
+
+    #[test]
+    fn chunks_are_merged() {
+        let mut response = empty_response();
+        response.add_chunk(&[&1, &2, &3], Ok((vec![1, 2], vec![3])));
+        response.add_chunk(&[&4, &5], Err(Error::ServerError(503, "down".to_string())));
+        response.add_chunk(&[&6, &7], Ok((vec![6], vec![7])));
+        response.add_chunk(&[&8], Err(Error::NotFound("gone".to_string())));
+
+        assert_eq!(response.found, vec![1, 2, 6]);
+        assert_eq!(response.missing, vec![3, 7]);
+        assert_eq!(response.failed, vec![4, 5, 8]);
+        assert!(matches!(
+            response.errors.as_slice(),
+            [Error::ServerError(503, _), Error::NotFound(_)]
+        ));
+    }
+
+    // Every title exists, unless a request asks for 250 with other IDs
+    fn every_title(path: &str) -> (u16, String) {
+        let ids = path
+            .split("ids=")
+            .nth(1)
+            .unwrap_or_default()
+            .split(',')
+            .collect::<Vec<&str>>();
+        if ids.len() > 1 && ids.contains(&"250") {
+            return (400, r#"{"text": "bad request"}"#.to_string());
+        }
+        let found = ids
+            .iter()
+            .map(|id| format!(r#"{{"id": {}, "name": "Title {}"}}"#, id, id))
+            .collect::<Vec<String>>();
+        (200, format!("[{}]", found.join(",")))
+    }
+
+    fn ids_per_request(requests: &Requests) -> Vec<usize> {
+        let mut sizes = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|path| path.split(',').count())
+            .collect::<Vec<usize>>();
+        sizes.sort_unstable();
+        sizes
+    }
+
+    #[tokio::test]
+    async fn many_ids_are_split_into_chunks() {
+        let (gateway, requests) = fake_gateway(every_title).await;
+        let client = client(&gateway);
+        let ids = (1..=200).chain(300..=449).collect::<Vec<usize>>();
+
+        let progress = std::sync::Mutex::new(vec![]);
+        let response = client
+            .request_many_with_progress::<Title, usize, _>(&ids, |done, total| {
+                progress.lock().unwrap().push((done, total))
+            })
+            .await;
+        assert_eq!(response.found.len(), 350);
+        assert!(response.failed.is_empty());
+        assert_eq!(ids_per_request(&requests), vec![150, 200]);
+        assert_eq!(progress.into_inner().unwrap(), vec![(1, 2), (2, 2)]);
+    }
+
+    #[tokio::test]
+    async fn chunks_ask_for_at_most_the_gateway_limit() {
+        let (gateway, requests) = fake_gateway(every_title).await;
+        let client = client(&gateway);
+        let ids = (1000..1000 + 2 * MAX_IDS_PER_REQUEST + 1).collect::<Vec<usize>>();
+        client.request_many::<Title, usize>(&ids).await;
+        assert_eq!(ids_per_request(&requests), vec![1, 200, 200]);
+    }
+
+    #[tokio::test]
+    async fn failed_chunk_keeps_the_others() {
+        let (gateway, _) = fake_gateway(every_title).await;
+        let client = client(&gateway);
+        let ids = (1..=450).collect::<Vec<usize>>();
+
+        let response = client.request_many::<Title, usize>(&ids).await;
+        // The second chunk of 201 to 400 failed
+        assert_eq!(response.found.len(), 250);
+        assert_eq!(response.failed, (201..=400).collect::<Vec<usize>>());
+        assert!(matches!(
+            response.errors.as_slice(),
+            [Error::Unexpected(400, _)]
+        ));
+        assert!(response.missing.is_empty());
+
+        // The failed IDs weren't remembered as missing, so they're asked for again
+        let response = client.request_many::<Title, usize>(&ids).await;
+        assert_eq!(response.found.len(), 250);
+        assert_eq!(response.failed.len(), 200);
+    }
+
+    // Titles 1 to 3, all at once or by ID
+    fn all_titles(path: &str) -> (u16, String) {
+        if path == "/v2/titles?ids=all" {
+            titles("/v2/titles?ids=1,2,3")
+        } else {
+            titles(path)
+        }
+    }
+
+    #[tokio::test]
+    async fn request_all_asks_for_ids_all() {
+        let (gateway, requests) = fake_gateway(all_titles).await;
+        let client = client(&gateway);
+
+        let mut all = client.request_all::<Title, usize>().await.unwrap();
+        all.sort_by_key(|title| title.id);
+        assert_eq!(
+            all.iter().map(|title| title.id).collect::<Vec<usize>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(*requests.lock().unwrap(), vec!["/v2/titles?ids=all"]);
+
+        // Each item was cached on its own
+        let response = client.request_many::<Title, usize>(&[1, 3]).await;
+        assert_eq!(response.found.len(), 2);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        assert!(matches!(
+            client.request_all::<Achievement, usize>().await,
+            Err(Error::Unsupported(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn request_all_uses_the_cache_only_once_it_is_complete() {
+        let (gateway, requests) = fake_gateway(all_titles).await;
+        let client = client(&gateway);
+
+        // Some titles are cached, but not all of them
+        client.request_many::<Title, usize>(&[1]).await;
+        assert_eq!(client.request_all::<Title, usize>().await.unwrap().len(), 3);
+        assert_eq!(
+            requests.lock().unwrap().last().unwrap(),
+            "/v2/titles?ids=all"
+        );
+
+        let requests_before = requests.lock().unwrap().len();
+        assert_eq!(client.request_all::<Title, usize>().await.unwrap().len(), 3);
+        assert_eq!(requests.lock().unwrap().len(), requests_before);
+    }
+}
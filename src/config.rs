@@ -35,13 +35,17 @@ pub struct Config {
     pub cache_compression: bool,
     #[serde(default = "default_starting_tab")]
     pub starting_tab: usize,
+    #[serde(default = "default_alert_lead_time", with = "duration_seconds")]
+    pub alert_lead_time: Duration,
+    #[serde(default = "default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
 }
 
 impl Config {
     pub fn load(options: Options) -> Result<Config> {
         if let Some(ref config_path) = options.config_path {
             let mut config =
-                Self::open_config(&config_path).and_then(|config| Self::parse_config(&config))?;
+                Self::open_config(config_path).and_then(|config| Self::parse_config(&config))?;
             config.options_override(options);
             if config.apikey.eq("~") {
                 Err(Error::MissingApiKey)
@@ -94,9 +98,10 @@ impl Config {
             if let Some(config_directory) = path.parent() {
                 fs::create_dir_all(config_directory).map_err(Error::Io)?;
             }
-            if let Ok(_) = fs::File::create(path)
+            if fs::File::create(path)
                 .map_err(Error::Io)?
                 .write_all(CONFIG_TXT.as_bytes())
+                .is_ok()
             {
                 Ok(CONFIG_TXT.to_string())
             } else {
@@ -129,6 +134,14 @@ fn default_starting_tab() -> usize {
     1
 }
 
+fn default_alert_lead_time() -> Duration {
+    Duration::minutes(5)
+}
+
+fn default_max_concurrent_requests() -> usize {
+    4
+}
+
 mod duration_seconds {
     use core::fmt;
 
@@ -238,4 +251,10 @@ apikey:
 # verbose: true
 #
 # Which tab to open when the application is started
-# starting_tab: 4"##;
+# starting_tab: 4
+#
+# How long (in seconds) before an event starts to alert about it on the timers tab
+# alert_lead_time: 300 # 5 minutes
+#
+# How many requests can be made to the gateway at the same time when loading a lot of data
+# max_concurrent_requests: 4"##;
@@ -1,7 +1,10 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
-    api::{Achievement, AllAccountAchievements, Dailies},
+    api::{
+        Account, Achievement, AchievementCategory, AchievementGroup, AllAccountAchievements,
+        Character, Dailies, Item, ItemPrice, Mastery, Recipe, Title,
+    },
     bookmarks::Bookmark,
     tracks::Track,
 };
@@ -11,10 +14,60 @@ pub enum Event {
     Quit,
     AddTrack(Track),
     AddBookmark(Bookmark),
+    /// Replace the bookmark with the first one's name with the second
+    ReplaceBookmark(Bookmark, Bookmark),
     ToggleTrack(Track),
+    /// Replace the first track with the second
+    ReplaceTrack(Track, Track),
     AccountAchievementsLoaded(AllAccountAchievements),
     AchievementsLoaded(HashSet<Achievement>),
+    AchievementGroupsLoaded(HashSet<AchievementGroup>),
+    AchievementCategoriesLoaded(HashSet<AchievementCategory>),
     FetchedDailies(Dailies),
+    FetchedTomorrowDailies(Dailies),
+    AccountLoaded(Account),
+    CharactersLoaded(Vec<Character>),
+    DailyChecklistLoaded(DailyChecklist),
+    DailyChecklistDone(DailyChecklist),
+    RewardsLoaded(Rewards),
+    ItemsFound(Vec<Item>),
+    ItemDetailsLoaded(ItemDetails),
+    /// Open the item lookup for an item
+    ShowItem(usize),
+    /// Select an achievement in the achievements tab
+    ShowAchievement(usize),
     StatusMessage(String),
+    /// An event on the timers tab is about to start
+    Alert(String),
     ClearStatusMessage,
+    ClearAlert,
+}
+
+/// Everything the item lookup knows about a single item
+#[derive(Clone, Debug)]
+pub struct ItemDetails {
+    pub id: usize,
+    pub price: Option<ItemPrice>,
+    /// Recipes that craft the item
+    pub produced_by: Vec<Recipe>,
+    /// Recipes that use the item as an ingredient
+    pub used_in: Vec<Recipe>,
+    /// The other items referenced by the recipes
+    pub items: Vec<Item>,
+}
+
+/// The items, titles and masteries that achievements reward, by ID
+#[derive(Clone, Debug, Default)]
+pub struct Rewards {
+    pub items: HashMap<usize, Item>,
+    pub titles: HashMap<usize, Title>,
+    pub masteries: HashMap<usize, Mastery>,
+}
+
+/// Things that can only be done once per day until the daily reset
+#[derive(Clone, Debug, Default)]
+pub struct DailyChecklist {
+    pub crafting: Vec<String>,
+    pub map_chests: Vec<String>,
+    pub world_bosses: Vec<String>,
 }
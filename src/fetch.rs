@@ -1,69 +1,222 @@
-use std::{collections::HashSet, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    time::Duration,
+};
 
+use futures::StreamExt;
 use log::debug;
-use tokio::sync::mpsc::UnboundedSender;
+use tokio::{
+    join,
+    sync::mpsc::{UnboundedReceiver, UnboundedSender},
+};
 
 use crate::{
-    api::{Achievement, AllAccountAchievements, AllAchievementIDs, Dailies},
-    client::CachedClient,
-    events::Event,
+    api::{
+        endpoints::MAX_PAGE_SIZE, Account, AccountDailyCrafting, AccountMapChests,
+        AccountWorldBosses, Achievement, AchievementCategory, AchievementGroup,
+        AllAccountAchievements, AllAchievementCategoryIDs, AllAchievementGroupIDs,
+        AllAchievementIDs, AllCharacters, AllDailyCrafting, AllMapChests, AllWorldBosses, Dailies,
+        Item, ItemPrice, Mastery, Recipe, RecipeSearch, RecipeSearchResults, Reward, Title,
+        TomorrowDailies,
+    },
+    client::{CachedClient, Error, ManyResponse},
+    events::{DailyChecklist, Event, ItemDetails, Rewards},
+    reset::Reset,
 };
 
+/// Requests from the UI for data that isn't fetched automatically
+#[derive(Debug)]
+pub enum Request {
+    /// Look up items by ID or by name
+    SearchItems(String),
+    /// Load prices and recipes for an item
+    ItemDetails(usize),
+}
+
+/// How often to check whether the game was updated
+const BUILD_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// How long after the daily reset to wait before asking for the new dailies, so the gateway has
+/// time to catch up
+const DAILY_RESET_DELAY: Duration = Duration::from_secs(30);
+
 pub struct Fetch {
     client: CachedClient,
     tx_event: UnboundedSender<Event>,
-    all_achievement_ids: Vec<usize>,
+    rx_request: Option<UnboundedReceiver<Request>>,
 }
 
 impl Fetch {
-    pub fn new(client: CachedClient, tx_event: UnboundedSender<Event>) -> Fetch {
+    pub fn new(
+        client: CachedClient,
+        tx_event: UnboundedSender<Event>,
+        rx_request: UnboundedReceiver<Request>,
+    ) -> Fetch {
         Fetch {
             client,
             tx_event,
-            all_achievement_ids: Vec::default(),
+            rx_request: Some(rx_request),
         }
     }
 
     pub async fn run(mut self, fetch_tick: u64) {
-        // TODO Error handling
-        // Fetch all the existing achievement IDs
-        self.all_achievement_ids = match self.client.request::<AllAchievementIDs>().await {
-            Ok(achievement_ids) => Some(achievement_ids.0),
-            Err(_) => None,
+        let rx_request = self.rx_request.take();
+
+        // Anything cached from before a game update is expired before it's loaded
+        self.check_build().await;
+
+        // Requests from the UI are handled while the rest of the data is loading
+        join!(
+            self.fetch_all(fetch_tick),
+            self.handle_requests(rx_request),
+            self.loop_check_build(),
+            self.loop_daily_reset()
+        );
+    }
+
+    // Errors are only returned after any retries, so they're all shown in the status bar
+    fn report_error(&self, what: &str, err: Error) {
+        debug!("Error fetching {}: {:?}", what, err);
+        let _ = self.tx_event.send(Event::StatusMessage(format!(
+            "Could not load {}: {}",
+            what,
+            err.summary()
+        )));
+    }
+
+    // Missing IDs are skipped, but it's worth knowing how many there were
+    fn report_missing<P: Debug>(&self, what: &str, missing: &[P]) {
+        if missing.is_empty() {
+            return;
+        }
+        debug!("Missing {}: {:?}", what, missing);
+        let _ = self.tx_event.send(Event::StatusMessage(format!(
+            "Skipped {} {} that could not be found",
+            missing.len(),
+            what
+        )));
+    }
+
+    // Reports anything that went wrong with a request for many items and returns what was found
+    fn take_found<E, P: Debug>(&self, what: &str, response: ManyResponse<E, P>) -> Vec<E> {
+        self.report_missing(what, &response.missing);
+        if !response.failed.is_empty() {
+            debug!("Failed to fetch {}: {:?}", what, response.failed);
+        }
+        for err in response.errors {
+            self.report_error(what, err);
         }
-        .unwrap_or_default();
+        response.found
+    }
+
+    // Checks whether the game was updated. Returns true if it was.
+    async fn check_build(&self) -> bool {
+        let updated = match self.client.check_build().await {
+            Ok(Some(build)) => {
+                let _ = self.tx_event.send(Event::StatusMessage(format!(
+                    "Game updated to build {}",
+                    build
+                )));
+                true
+            }
+            Ok(None) => false,
+            Err(err) => {
+                self.report_error("the game build", err);
+                false
+            }
+        };
+        self.client.write_cache();
+        updated
+    }
+
+    // A loop to periodically check for game updates, reloading everything that might have changed
+    async fn loop_check_build(&self) {
+        loop {
+            tokio::time::sleep(BUILD_CHECK_INTERVAL).await;
+            if self.check_build().await {
+                let rewards = self.fetch_achievements().await;
+                self.fetch_achievement_groups().await;
+                self.fetch_achievement_categories().await;
+                self.fetch_dailies().await;
+                self.fetch_daily_checklist().await;
+                self.fetch_rewards(&rewards).await;
+                self.index_items().await;
+            }
+        }
+    }
+
+    // Dailies change at the daily reset, so they're loaded again once it happens
+    async fn loop_daily_reset(&self) {
+        loop {
+            let until_reset = Reset::Daily.until_next().to_std().unwrap_or_default();
+            tokio::time::sleep(until_reset + DAILY_RESET_DELAY).await;
+            self.fetch_dailies().await;
+            self.fetch_daily_checklist().await;
+        }
+    }
 
-        self.fetch_achievements().await;
+    async fn fetch_all(&self, fetch_tick: u64) {
+        let rewards = self.fetch_achievements().await;
+        self.fetch_achievement_groups().await;
+        self.fetch_achievement_categories().await;
         self.fetch_dailies().await;
-        self.loop_fetch_account_achievements(fetch_tick).await;
-    }
-
-    // Requests and caches all the achievements in the game
-    async fn fetch_achievements(&self) {
-        let paged_ids = self.all_achievement_ids.chunks(100);
-        let total_pages = paged_ids.clone().count();
-        let mut all_achievements = HashSet::with_capacity(self.all_achievement_ids.len());
-        for (current_page, ids) in paged_ids.enumerate() {
-            match self
-                .client
-                .request_many::<Achievement, usize>(&ids.to_vec())
-                .await
-            {
-                Ok(achievement_page) => {
-                    let progress: f64 = current_page as f64 / (total_pages - 1) as f64;
-                    for achievement in achievement_page {
-                        all_achievements.insert(achievement);
-                    }
+        self.fetch_account().await;
+        self.fetch_daily_checklist().await;
+        self.fetch_rewards(&rewards).await;
+        join!(
+            self.index_items(),
+            self.loop_fetch_account_achievements(fetch_tick)
+        );
+    }
+
+    async fn handle_requests(&self, rx_request: Option<UnboundedReceiver<Request>>) {
+        let mut rx_request = match rx_request {
+            Some(rx_request) => rx_request,
+            None => return,
+        };
+
+        while let Some(request) = rx_request.recv().await {
+            match request {
+                Request::SearchItems(query) => self.search_items(query.trim()).await,
+                Request::ItemDetails(id) => self.fetch_item_details(id).await,
+            }
+        }
+    }
+
+    // Requests and caches all the achievements in the game. Returns the rewards of all the
+    // achievements so they can be looked up afterwards.
+    async fn fetch_achievements(&self) -> Vec<Reward> {
+        // Fetch all the existing achievement IDs
+        let all_achievement_ids = match self.client.request::<AllAchievementIDs>().await {
+            Ok(achievement_ids) => achievement_ids.0,
+            Err(err) => {
+                self.report_error("achievements", err);
+                vec![]
+            }
+        };
+
+        // Requests are made a few at a time, so progress is counted as each one finishes
+        let achievements = self
+            .client
+            .request_many_with_progress::<Achievement, usize, _>(
+                &all_achievement_ids,
+                |done, total| {
                     let _ = self.tx_event.send(Event::StatusMessage(format!(
                         "Loading achievements... {}%",
-                        (progress * 100.0) as u64
+                        done * 100 / total
                     )));
-                }
-                Err(err) => {
-                    debug!("Error fetching Achievements: {:?}", err);
-                }
-            }
-        }
+                },
+            )
+            .await;
+        let all_achievements = self
+            .take_found("achievements", achievements)
+            .into_iter()
+            .collect::<HashSet<Achievement>>();
+        let rewards = all_achievements
+            .iter()
+            .filter_map(|achievement| achievement.rewards.clone())
+            .flatten()
+            .collect();
         let _ = self
             .tx_event
             .send(Event::AchievementsLoaded(all_achievements));
@@ -71,12 +224,111 @@ impl Fetch {
             "Done loading achievements...".to_string(),
         ));
         self.client.write_cache();
+        rewards
+    }
+
+    // Requests and caches the top-level achievement groups
+    async fn fetch_achievement_groups(&self) {
+        let group_ids = match self.client.request::<AllAchievementGroupIDs>().await {
+            Ok(group_ids) => group_ids.0,
+            Err(err) => {
+                debug!("Error fetching AllAchievementGroupIDs: {:?}", err);
+                return;
+            }
+        };
+
+        let groups = self
+            .client
+            .request_many::<AchievementGroup, String>(&group_ids)
+            .await;
+        let groups = self.take_found("achievement groups", groups);
+        let _ = self
+            .tx_event
+            .send(Event::AchievementGroupsLoaded(groups.into_iter().collect()));
+        self.client.write_cache();
+    }
+
+    // Requests and caches the achievement categories that belong to the groups
+    async fn fetch_achievement_categories(&self) {
+        let category_ids = match self.client.request::<AllAchievementCategoryIDs>().await {
+            Ok(category_ids) => category_ids.0,
+            Err(err) => {
+                debug!("Error fetching AllAchievementCategoryIDs: {:?}", err);
+                return;
+            }
+        };
+
+        let categories = self
+            .client
+            .request_many::<AchievementCategory, usize>(&category_ids)
+            .await;
+        let categories = self.take_found("achievement categories", categories);
+        let _ = self.tx_event.send(Event::AchievementCategoriesLoaded(
+            categories.into_iter().collect(),
+        ));
+        self.client.write_cache();
+    }
+
+    // Requests and caches the items, titles and masteries that achievements reward
+    async fn fetch_rewards(&self, rewards: &[Reward]) {
+        let mut item_ids = HashSet::new();
+        let mut title_ids = HashSet::new();
+        let mut mastery_ids = HashSet::new();
+        for reward in rewards {
+            match reward {
+                Reward::Item { id, .. } => item_ids.insert(*id),
+                Reward::Title { id } => title_ids.insert(*id),
+                Reward::Mastery { id, .. } => mastery_ids.insert(*id),
+                Reward::Coins { .. } => false,
+            };
+        }
+
+        let item_ids = item_ids.into_iter().collect::<Vec<usize>>();
+        let items = self.client.request_many::<Item, usize>(&item_ids).await;
+        let items = self
+            .take_found("items", items)
+            .into_iter()
+            .map(|item| (item.id, item))
+            .collect();
+
+        // There are few enough titles and masteries to request them all at once
+        let titles = match self.client.request_all::<Title, usize>().await {
+            Ok(titles) => titles
+                .into_iter()
+                .filter(|title| title_ids.contains(&title.id))
+                .map(|title| (title.id, title))
+                .collect(),
+            Err(err) => {
+                self.report_error("titles", err);
+                HashMap::new()
+            }
+        };
+
+        let masteries = match self.client.request_all::<Mastery, usize>().await {
+            Ok(masteries) => masteries
+                .into_iter()
+                .filter(|mastery| mastery_ids.contains(&mastery.id))
+                .map(|mastery| (mastery.id, mastery))
+                .collect(),
+            Err(err) => {
+                self.report_error("masteries", err);
+                HashMap::new()
+            }
+        };
+
+        let _ = self.tx_event.send(Event::RewardsLoaded(Rewards {
+            items,
+            titles,
+            masteries,
+        }));
+        self.client.write_cache();
     }
 
     // A loop to periodically update account achievement progress
     async fn loop_fetch_account_achievements(&self, tick: u64) {
         loop {
             self.fetch_account_achievements().await;
+            self.fetch_daily_checklist_done().await;
             tokio::time::sleep(Duration::from_secs(tick)).await;
         }
     }
@@ -92,7 +344,7 @@ impl Fetch {
                     "Updated achievement progress".to_string(),
                 ));
             }
-            Err(err) => debug!("Error fetching AllAccountAchievements: {:?}", err),
+            Err(err) => self.report_error("achievement progress", err),
         }
         self.client.write_cache();
     }
@@ -102,7 +354,190 @@ impl Fetch {
             Ok(dailies) => {
                 let _ = self.tx_event.send(Event::FetchedDailies(dailies));
             }
-            Err(err) => debug!("Error fetching Dailies: {:?}", err),
+            Err(err) => self.report_error("dailies", err),
+        }
+        match self.client.request::<TomorrowDailies>().await {
+            Ok(dailies) => {
+                let _ = self.tx_event.send(Event::FetchedTomorrowDailies(dailies.0));
+            }
+            Err(err) => self.report_error("tomorrow's dailies", err),
+        }
+        self.client.write_cache();
+    }
+
+    // Requests which expansions the account owns and the levels of its characters
+    async fn fetch_account(&self) {
+        match self.client.request::<Account>().await {
+            Ok(account) => {
+                let _ = self.tx_event.send(Event::AccountLoaded(account));
+            }
+            Err(err) => self.report_error("account", err),
+        }
+        match self.client.request::<AllCharacters>().await {
+            Ok(characters) => {
+                let _ = self.tx_event.send(Event::CharactersLoaded(characters.0));
+            }
+            Err(err) => self.report_error("characters", err),
+        }
+        self.client.write_cache();
+    }
+
+    // Requests everything that can only be done once per day
+    async fn fetch_daily_checklist(&self) {
+        let crafting = self.client.request::<AllDailyCrafting>().await;
+        let map_chests = self.client.request::<AllMapChests>().await;
+        let world_bosses = self.client.request::<AllWorldBosses>().await;
+        match (crafting, map_chests, world_bosses) {
+            (Ok(crafting), Ok(map_chests), Ok(world_bosses)) => {
+                let _ = self
+                    .tx_event
+                    .send(Event::DailyChecklistLoaded(DailyChecklist {
+                        crafting: crafting.0,
+                        map_chests: map_chests.0,
+                        world_bosses: world_bosses.0,
+                    }));
+            }
+            (crafting, map_chests, world_bosses) => {
+                for err in vec![crafting.err(), map_chests.err(), world_bosses.err()]
+                    .into_iter()
+                    .flatten()
+                {
+                    self.report_error("the daily checklist", err);
+                }
+            }
         }
+        self.client.write_cache();
+    }
+
+    // Update which of the daily checklist has been done by the user today
+    async fn fetch_daily_checklist_done(&self) {
+        let crafting = self.client.request::<AccountDailyCrafting>().await;
+        let map_chests = self.client.request::<AccountMapChests>().await;
+        let world_bosses = self.client.request::<AccountWorldBosses>().await;
+        match (crafting, map_chests, world_bosses) {
+            (Ok(crafting), Ok(map_chests), Ok(world_bosses)) => {
+                let _ = self
+                    .tx_event
+                    .send(Event::DailyChecklistDone(DailyChecklist {
+                        crafting: crafting.0,
+                        map_chests: map_chests.0,
+                        world_bosses: world_bosses.0,
+                    }));
+            }
+            (crafting, map_chests, world_bosses) => {
+                for err in vec![crafting.err(), map_chests.err(), world_bosses.err()]
+                    .into_iter()
+                    .flatten()
+                {
+                    self.report_error("daily checklist progress", err);
+                }
+            }
+        }
+        self.client.write_cache();
+    }
+
+    // Walks every page of items so they can be searched by name, since the gateway can't do that
+    // itself. It's done again once the cached items expire, like after a game update.
+    async fn index_items(&self) {
+        if self.client.has_every_page::<Item, usize>() {
+            return;
+        }
+
+        let mut pages = Box::pin(self.client.request_pages::<Item, usize>(MAX_PAGE_SIZE));
+        while let Some(page) = pages.next().await {
+            match page {
+                Ok(page) => {
+                    let _ = self.tx_event.send(Event::StatusMessage(format!(
+                        "Indexing items... {}%",
+                        (page.page + 1) * 100 / page.page_total.max(1)
+                    )));
+                }
+                Err(err) => self.report_error("the item index", err),
+            }
+        }
+        self.client.write_cache();
+    }
+
+    // Look up an item by its ID, or search the item index by name. Until the index is done
+    // loading, a name only finds the items indexed so far or looked up before.
+    async fn search_items(&self, query: &str) {
+        let (items, searched) = match query.parse::<usize>() {
+            Ok(id) => {
+                let items = self.client.request_many::<Item, usize>(&[id]).await;
+                for err in items.errors {
+                    self.report_error("items", err);
+                }
+                (items.found, "items")
+            }
+            Err(_) if self.client.has_every_page::<Item, usize>() => {
+                (self.client.search_cached_items(query), "items")
+            }
+            Err(_) => (self.client.search_cached_items(query), "indexed items"),
+        };
+
+        let message = if items.is_empty() {
+            format!("No {} found for '{}'", searched, query)
+        } else {
+            format!("Found {} {} for '{}'", items.len(), searched, query)
+        };
+        let _ = self.tx_event.send(Event::StatusMessage(message));
+        let _ = self.tx_event.send(Event::ItemsFound(items));
+        self.client.write_cache();
+    }
+
+    // Requests the price of an item, the recipes it's part of, and the other items in those recipes
+    async fn fetch_item_details(&self, id: usize) {
+        // Items that can't be sold on the trading post have no price, so it being missing is fine
+        let prices = self.client.request_many::<ItemPrice, usize>(&[id]).await;
+        for err in prices.errors {
+            self.report_error("item prices", err);
+        }
+        let price = prices.found.into_iter().next();
+
+        let produced_by = self.fetch_recipes(RecipeSearch::Output(id)).await;
+        let used_in = self.fetch_recipes(RecipeSearch::Input(id)).await;
+
+        let mut item_ids = produced_by
+            .iter()
+            .chain(used_in.iter())
+            .flat_map(|recipe| {
+                recipe
+                    .ingredients
+                    .iter()
+                    .map(|ingredient| ingredient.item_id)
+                    .chain(Some(recipe.output_item_id))
+            })
+            .collect::<Vec<usize>>();
+        item_ids.sort_unstable();
+        item_ids.dedup();
+
+        let items = self.client.request_many::<Item, usize>(&item_ids).await;
+        let items = self.take_found("items", items);
+
+        let _ = self.tx_event.send(Event::ItemDetailsLoaded(ItemDetails {
+            id,
+            price,
+            produced_by,
+            used_in,
+            items,
+        }));
+        self.client.write_cache();
+    }
+
+    async fn fetch_recipes(&self, search: RecipeSearch) -> Vec<Recipe> {
+        let recipe_ids = match self
+            .client
+            .request_with_param::<RecipeSearchResults, RecipeSearch>(&search)
+            .await
+        {
+            Ok(results) => results.0,
+            Err(err) => {
+                self.report_error(&search.to_string(), err);
+                return vec![];
+            }
+        };
+
+        let recipes = self.client.request_many::<Recipe, usize>(&recipe_ids).await;
+        self.take_found("recipes", recipes)
     }
 }
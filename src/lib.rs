@@ -1,12 +1,16 @@
 pub mod api;
 pub mod bookmarks;
 pub mod cache;
+pub mod chatlink;
 pub mod cli;
 pub mod client;
 pub mod config;
 pub mod events;
 pub mod fetch;
 pub mod log;
+pub mod query;
+pub mod reset;
+pub mod sort;
 pub mod state;
 pub mod tracks;
 
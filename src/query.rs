@@ -0,0 +1,611 @@
+//! A small query language for filtering achievements
+//!
+//! A query is made of terms that are combined with `AND`, `OR` and negation. Terms next to each
+//! other without an operator are combined with `AND`. For example:
+//!
+//! ```text
+//! flag:Daily -done:yes (reward:title OR points>=10) desc:"jumping puzzle"
+//! ```
+//!
+//! A term without a field matches against the name of the achievement, and so does a term whose
+//! text before the `:` isn't a field, like `Hero's:`. A term that is quoted as a whole, like
+//! `"Hero's: Tyria"`, is always matched against the name.
+
+use std::{fmt, iter::Peekable, str::Chars};
+
+use crate::api::{AccountAchievement, Achievement, Reward};
+
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    UnexpectedEnd,
+    UnexpectedToken(String),
+    UnclosedQuote,
+    UnclosedParen,
+    MissingValue(String),
+    InvalidValue { field: String, value: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnexpectedEnd => write!(f, "unexpected end of query"),
+            Error::UnexpectedToken(token) => write!(f, "unexpected '{}'", token),
+            Error::UnclosedQuote => write!(f, "missing closing quote"),
+            Error::UnclosedParen => write!(f, "missing closing parenthesis"),
+            Error::MissingValue(field) => write!(f, "missing value for '{}'", field),
+            Error::InvalidValue { field, value } => {
+                write!(f, "invalid value '{}' for '{}'", value, field)
+            }
+        }
+    }
+}
+
+/// The fields of an achievement that can be queried
+#[derive(Debug, PartialEq)]
+pub enum Field {
+    Name,
+    Description,
+    Flag,
+    Type,
+    Done,
+    Tracked,
+    Reward,
+    Points,
+    Id,
+}
+
+impl Field {
+    fn parse(field: &str) -> Option<Self> {
+        match field.to_lowercase().as_str() {
+            "name" => Some(Field::Name),
+            "desc" | "description" => Some(Field::Description),
+            "flag" => Some(Field::Flag),
+            "type" => Some(Field::Type),
+            "done" => Some(Field::Done),
+            "tracked" => Some(Field::Tracked),
+            "reward" => Some(Field::Reward),
+            "points" => Some(Field::Points),
+            "id" => Some(Field::Id),
+            _ => None,
+        }
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(self, Field::Points | Field::Id)
+    }
+}
+
+/// How a field is compared to the value of a term
+#[derive(Debug, PartialEq)]
+pub enum Comparison {
+    Equal,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+impl Comparison {
+    fn compare(&self, lhs: usize, rhs: usize) -> bool {
+        match self {
+            Comparison::Equal => lhs == rhs,
+            Comparison::Greater => lhs > rhs,
+            Comparison::GreaterOrEqual => lhs >= rhs,
+            Comparison::Less => lhs < rhs,
+            Comparison::LessOrEqual => lhs <= rhs,
+        }
+    }
+}
+
+/// A single condition on an achievement
+#[derive(Debug, PartialEq)]
+pub enum Term {
+    Text(Field, String),
+    Boolean(Field, bool),
+    Number(Field, Comparison, usize),
+}
+
+impl Term {
+    fn parse(term: &str, quoted: bool) -> Result<Self> {
+        if quoted {
+            return Ok(Term::Text(Field::Name, term.to_lowercase()));
+        }
+
+        let operator = term
+            .char_indices()
+            .find(|(_, c)| matches!(c, ':' | '=' | '>' | '<'));
+
+        let (field, comparison, value) = match operator {
+            None => return Ok(Term::Text(Field::Name, term.to_lowercase())),
+            Some((index, c)) => {
+                let (field, rest) = term.split_at(index);
+                // Names can contain these too, so it's only a field if it's one we know
+                let field = match Field::parse(field) {
+                    Some(field) => field,
+                    None => return Ok(Term::Text(Field::Name, term.to_lowercase())),
+                };
+                let rest = &rest[c.len_utf8()..];
+                let (comparison, value) = match (c, rest.strip_prefix('=')) {
+                    ('>', Some(value)) => (Comparison::GreaterOrEqual, value),
+                    ('<', Some(value)) => (Comparison::LessOrEqual, value),
+                    ('>', None) => (Comparison::Greater, rest),
+                    ('<', None) => (Comparison::Less, rest),
+                    (_, _) => (Comparison::Equal, rest),
+                };
+                (field, comparison, value)
+            }
+        };
+
+        if value.is_empty() {
+            return Err(Error::MissingValue(field_name(term)));
+        }
+
+        let invalid_value = || Error::InvalidValue {
+            field: field_name(term),
+            value: value.to_string(),
+        };
+
+        if field.is_numeric() {
+            return value
+                .parse::<usize>()
+                .map(|number| Term::Number(field, comparison, number))
+                .map_err(|_| invalid_value());
+        }
+
+        if comparison != Comparison::Equal {
+            return Err(invalid_value());
+        }
+
+        match field {
+            Field::Done | Field::Tracked => match value.to_lowercase().as_str() {
+                "yes" | "true" | "y" => Ok(Term::Boolean(field, true)),
+                "no" | "false" | "n" => Ok(Term::Boolean(field, false)),
+                _ => Err(invalid_value()),
+            },
+            Field::Reward => match value.to_lowercase().as_str() {
+                "coin" | "coins" | "item" | "mastery" | "title" => {
+                    Ok(Term::Text(field, value.to_lowercase()))
+                }
+                _ => Err(invalid_value()),
+            },
+            _ => Ok(Term::Text(field, value.to_lowercase())),
+        }
+    }
+
+    fn matches(&self, subject: &Subject) -> bool {
+        let achievement = subject.achievement;
+        match self {
+            Term::Text(Field::Name, text) => achievement.name.to_lowercase().contains(text),
+            Term::Text(Field::Description, text) => {
+                achievement.description.to_lowercase().contains(text)
+            }
+            Term::Text(Field::Flag, flag) => achievement
+                .flags
+                .iter()
+                .any(|achievement_flag| achievement_flag.to_lowercase() == *flag),
+            Term::Text(Field::Type, achievement_type) => {
+                achievement.achievement_type.to_lowercase() == *achievement_type
+            }
+            Term::Text(Field::Reward, kind) => {
+                achievement.rewards.as_ref().is_some_and(|rewards| {
+                    rewards.iter().any(|reward| match reward {
+                        Reward::Coins { .. } => kind.starts_with("coin"),
+                        Reward::Item { .. } => kind == "item",
+                        Reward::Mastery { .. } => kind == "mastery",
+                        Reward::Title { .. } => kind == "title",
+                    })
+                })
+            }
+            Term::Boolean(Field::Done, done) => {
+                subject
+                    .account_achievement
+                    .is_some_and(|account_achievement| account_achievement.done)
+                    == *done
+            }
+            Term::Boolean(Field::Tracked, tracked) => subject.tracked == *tracked,
+            Term::Number(Field::Points, comparison, points) => {
+                comparison.compare(achievement.total_points(), *points)
+            }
+            Term::Number(Field::Id, comparison, id) => comparison.compare(achievement.id, *id),
+            _ => false,
+        }
+    }
+}
+
+fn field_name(term: &str) -> String {
+    term.split(&[':', '=', '>', '<'][..])
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// The achievement a [Query] is evaluated against
+pub struct Subject<'a> {
+    pub achievement: &'a Achievement,
+    pub account_achievement: Option<&'a AccountAchievement>,
+    pub tracked: bool,
+}
+
+/// A parsed query that can be evaluated against achievements
+#[derive(Debug, PartialEq)]
+pub enum Query {
+    All,
+    Term(Term),
+    Not(Box<Query>),
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Result<Self> {
+        let mut tokens = tokenize(query)?.into_iter().peekable();
+        if tokens.peek().is_none() {
+            return Ok(Query::All);
+        }
+
+        let query = parse_or(&mut tokens)?;
+        match tokens.next() {
+            Some(token) => Err(Error::UnexpectedToken(token.to_string())),
+            None => Ok(query),
+        }
+    }
+
+    pub fn matches(&self, subject: &Subject) -> bool {
+        match self {
+            Query::All => true,
+            Query::Term(term) => term.matches(subject),
+            Query::Not(query) => !query.matches(subject),
+            Query::And(lhs, rhs) => lhs.matches(subject) && rhs.matches(subject),
+            Query::Or(lhs, rhs) => lhs.matches(subject) || rhs.matches(subject),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    OpenParen,
+    CloseParen,
+    And,
+    Or,
+    Not,
+    /// Whether the whole term was quoted, so it's only text
+    Term {
+        text: String,
+        quoted: bool,
+    },
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::OpenParen => write!(f, "("),
+            Token::CloseParen => write!(f, ")"),
+            Token::And => write!(f, "AND"),
+            Token::Or => write!(f, "OR"),
+            Token::Not => write!(f, "NOT"),
+            Token::Term { text, quoted: true } => write!(f, "\"{}\"", text),
+            Token::Term { text, .. } => write!(f, "{}", text),
+        }
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = query.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '(' => tokens.push(Token::OpenParen),
+            ')' => tokens.push(Token::CloseParen),
+            '|' => tokens.push(Token::Or),
+            '&' => tokens.push(Token::And),
+            '-' | '!' => tokens.push(Token::Not),
+            c => {
+                let (text, quoted) = read_term(c, &mut chars)?;
+                tokens.push(match (text.as_str(), quoted) {
+                    ("AND", false) => Token::And,
+                    ("OR", false) => Token::Or,
+                    ("NOT", false) => Token::Not,
+                    _ => Token::Term { text, quoted },
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+// Read a term up to the next whitespace or parenthesis. Anything between double quotes is read
+// verbatim so values can contain spaces. Also returns whether the term started with a quote.
+fn read_term(first: char, chars: &mut Peekable<Chars>) -> Result<(String, bool)> {
+    let mut term = String::new();
+    let mut quoted = first == '"';
+    if !quoted {
+        term.push(first);
+    }
+
+    while let Some(&c) = chars.peek() {
+        if !quoted && (c.is_whitespace() || c == '(' || c == ')') {
+            break;
+        }
+        chars.next();
+        if c == '"' {
+            quoted = !quoted;
+        } else {
+            term.push(c);
+        }
+    }
+
+    if quoted {
+        Err(Error::UnclosedQuote)
+    } else {
+        Ok((term, first == '"'))
+    }
+}
+
+type Tokens = Peekable<std::vec::IntoIter<Token>>;
+
+fn parse_or(tokens: &mut Tokens) -> Result<Query> {
+    let mut query = parse_and(tokens)?;
+    while tokens.peek() == Some(&Token::Or) {
+        tokens.next();
+        query = Query::Or(Box::new(query), Box::new(parse_and(tokens)?));
+    }
+    Ok(query)
+}
+
+fn parse_and(tokens: &mut Tokens) -> Result<Query> {
+    let mut query = parse_unary(tokens)?;
+    loop {
+        match tokens.peek() {
+            Some(Token::And) => {
+                tokens.next();
+            }
+            Some(Token::Term { .. }) | Some(Token::Not) | Some(Token::OpenParen) => {}
+            _ => break,
+        }
+        query = Query::And(Box::new(query), Box::new(parse_unary(tokens)?));
+    }
+    Ok(query)
+}
+
+fn parse_unary(tokens: &mut Tokens) -> Result<Query> {
+    match tokens.next() {
+        Some(Token::Not) => Ok(Query::Not(Box::new(parse_unary(tokens)?))),
+        Some(Token::OpenParen) => {
+            let query = parse_or(tokens)?;
+            match tokens.next() {
+                Some(Token::CloseParen) => Ok(query),
+                Some(token) => Err(Error::UnexpectedToken(token.to_string())),
+                None => Err(Error::UnclosedParen),
+            }
+        }
+        Some(Token::Term { text, quoted }) => Term::parse(&text, quoted).map(Query::Term),
+        Some(token) => Err(Error::UnexpectedToken(token.to_string())),
+        None => Err(Error::UnexpectedEnd),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(text: &str) -> Query {
+        Query::Term(Term::Text(Field::Name, text.to_string()))
+    }
+
+    fn and(lhs: Query, rhs: Query) -> Query {
+        Query::And(Box::new(lhs), Box::new(rhs))
+    }
+
+    fn or(lhs: Query, rhs: Query) -> Query {
+        Query::Or(Box::new(lhs), Box::new(rhs))
+    }
+
+    fn not(query: Query) -> Query {
+        Query::Not(Box::new(query))
+    }
+
+    fn points(comparison: Comparison, points: usize) -> Query {
+        Query::Term(Term::Number(Field::Points, comparison, points))
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(Query::parse("").unwrap(), Query::All);
+        assert_eq!(Query::parse("   ").unwrap(), Query::All);
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let expected = or(and(name("a"), name("b")), name("c"));
+        assert_eq!(Query::parse("a b OR c").unwrap(), expected);
+        assert_eq!(Query::parse("a AND b OR c").unwrap(), expected);
+        assert_eq!(Query::parse("a & b | c").unwrap(), expected);
+        assert_eq!(
+            Query::parse("a OR b c").unwrap(),
+            or(name("a"), and(name("b"), name("c")))
+        );
+    }
+
+    #[test]
+    fn negation() {
+        assert_eq!(Query::parse("-a").unwrap(), not(name("a")));
+        assert_eq!(Query::parse("!a").unwrap(), not(name("a")));
+        assert_eq!(Query::parse("NOT a").unwrap(), not(name("a")));
+        assert_eq!(Query::parse("--a").unwrap(), not(not(name("a"))));
+        assert_eq!(
+            Query::parse("-a b").unwrap(),
+            and(not(name("a")), name("b"))
+        );
+        assert_eq!(
+            Query::parse("-(a OR b)").unwrap(),
+            not(or(name("a"), name("b")))
+        );
+    }
+
+    #[test]
+    fn parentheses() {
+        assert_eq!(
+            Query::parse("a (b OR c)").unwrap(),
+            and(name("a"), or(name("b"), name("c")))
+        );
+        assert_eq!(Query::parse("((a))").unwrap(), name("a"));
+        assert_eq!(Query::parse("(a"), Err(Error::UnclosedParen));
+        assert_eq!(
+            Query::parse("a)"),
+            Err(Error::UnexpectedToken(")".to_string()))
+        );
+        assert_eq!(
+            Query::parse("()"),
+            Err(Error::UnexpectedToken(")".to_string()))
+        );
+    }
+
+    #[test]
+    fn dangling_operators() {
+        assert_eq!(Query::parse("a OR"), Err(Error::UnexpectedEnd));
+        assert_eq!(Query::parse("-"), Err(Error::UnexpectedEnd));
+        assert_eq!(
+            Query::parse("OR a"),
+            Err(Error::UnexpectedToken("OR".to_string()))
+        );
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_eq!(
+            Query::parse("points:5").unwrap(),
+            points(Comparison::Equal, 5)
+        );
+        assert_eq!(
+            Query::parse("points=5").unwrap(),
+            points(Comparison::Equal, 5)
+        );
+        assert_eq!(
+            Query::parse("points>5").unwrap(),
+            points(Comparison::Greater, 5)
+        );
+        assert_eq!(
+            Query::parse("points>=5").unwrap(),
+            points(Comparison::GreaterOrEqual, 5)
+        );
+        assert_eq!(
+            Query::parse("points<5").unwrap(),
+            points(Comparison::Less, 5)
+        );
+        assert_eq!(
+            Query::parse("points<=5").unwrap(),
+            points(Comparison::LessOrEqual, 5)
+        );
+        assert_eq!(
+            Query::parse("id=1234").unwrap(),
+            Query::Term(Term::Number(Field::Id, Comparison::Equal, 1234))
+        );
+
+        assert!(Comparison::Equal.compare(5, 5));
+        assert!(Comparison::Greater.compare(6, 5));
+        assert!(!Comparison::Greater.compare(5, 5));
+        assert!(Comparison::GreaterOrEqual.compare(5, 5));
+        assert!(Comparison::Less.compare(4, 5));
+        assert!(!Comparison::Less.compare(5, 5));
+        assert!(Comparison::LessOrEqual.compare(5, 5));
+    }
+
+    #[test]
+    fn invalid_values() {
+        let invalid = |field: &str, value: &str| {
+            Err(Error::InvalidValue {
+                field: field.to_string(),
+                value: value.to_string(),
+            })
+        };
+        assert_eq!(Query::parse("points:many"), invalid("points", "many"));
+        assert_eq!(Query::parse("desc>jump"), invalid("desc", "jump"));
+        assert_eq!(Query::parse("done:maybe"), invalid("done", "maybe"));
+        assert_eq!(Query::parse("reward:gold"), invalid("reward", "gold"));
+    }
+
+    #[test]
+    fn unknown_fields_are_part_of_the_name() {
+        assert_eq!(Query::parse("color:red").unwrap(), name("color:red"));
+        assert_eq!(
+            Query::parse("Hero's: Tyria").unwrap(),
+            and(name("hero's:"), name("tyria"))
+        );
+        assert_eq!(Query::parse("tier>3").unwrap(), name("tier>3"));
+    }
+
+    #[test]
+    fn missing_values() {
+        assert_eq!(
+            Query::parse("points>"),
+            Err(Error::MissingValue("points".to_string()))
+        );
+        assert_eq!(
+            Query::parse("flag:"),
+            Err(Error::MissingValue("flag".to_string()))
+        );
+        assert_eq!(
+            Query::parse("desc:\"\""),
+            Err(Error::MissingValue("desc".to_string()))
+        );
+    }
+
+    #[test]
+    fn quotes() {
+        assert_eq!(
+            Query::parse("desc:\"jumping puzzle\"").unwrap(),
+            Query::Term(Term::Text(Field::Description, "jumping puzzle".to_string()))
+        );
+        assert_eq!(
+            Query::parse("\"Hero's: Tyria\"").unwrap(),
+            name("hero's: tyria")
+        );
+        assert_eq!(
+            Query::parse("\"AND\" b").unwrap(),
+            and(name("and"), name("b"))
+        );
+        assert_eq!(Query::parse("\"jumping"), Err(Error::UnclosedQuote));
+        assert_eq!(Query::parse("desc:\"jumping"), Err(Error::UnclosedQuote));
+    }
+
+    #[test]
+    fn matches() {
+        let achievement: Achievement = serde_json::from_str(
+            r#"{
+                "id": 1234,
+                "name": "Hero's: Tyria",
+                "description": "Complete the jumping puzzle",
+                "requirement": "",
+                "locked_text": "",
+                "type": "Default",
+                "flags": ["Daily"],
+                "tiers": [{ "count": 1, "points": 5 }, { "count": 2, "points": 5 }],
+                "rewards": [{ "type": "Title", "id": 1 }]
+            }"#,
+        )
+        .unwrap();
+        let subject = Subject {
+            achievement: &achievement,
+            account_achievement: None,
+            tracked: true,
+        };
+        let matches = |query: &str| Query::parse(query).unwrap().matches(&subject);
+
+        assert!(matches(""));
+        assert!(matches("tyria"));
+        assert!(matches("\"Hero's: Tyria\""));
+        assert!(matches("Hero's: Tyria"));
+        assert!(matches("flag:daily desc:jumping"));
+        assert!(matches("type:default reward:title"));
+        assert!(!matches("reward:item"));
+        assert!(matches("points=10 id>=1234"));
+        assert!(!matches("points>10"));
+        assert!(matches("done:no tracked:yes"));
+        assert!(matches("-done:yes"));
+        assert!(matches("orr OR tyria"));
+        assert!(!matches("orr (tyria OR daily)"));
+    }
+}
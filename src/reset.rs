@@ -0,0 +1,137 @@
+//! When the game starts the next day and week
+//!
+//! Dailies reset at 00:00 UTC and weeklies on Monday at 07:30 UTC.
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reset {
+    Daily,
+    Weekly,
+}
+
+impl Reset {
+    /// The last reset at or before `now`
+    pub fn last(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let today = Utc.from_utc_datetime(&now.naive_utc().date().and_hms_opt(0, 0, 0).unwrap());
+        match self {
+            Reset::Daily => today,
+            Reset::Weekly => {
+                let monday = today - Duration::days(now.weekday().num_days_from_monday() as i64)
+                    + Duration::minutes(7 * 60 + 30);
+                if monday > now {
+                    monday - Duration::weeks(1)
+                } else {
+                    monday
+                }
+            }
+        }
+    }
+
+    /// The first reset after `now`
+    pub fn next(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        self.last(now) + self.period()
+    }
+
+    pub fn period(&self) -> Duration {
+        match self {
+            Reset::Daily => Duration::days(1),
+            Reset::Weekly => Duration::weeks(1),
+        }
+    }
+
+    /// How long until the next reset
+    pub fn until_next(&self) -> Duration {
+        let now = Utc::now();
+        self.next(now) - now
+    }
+
+    /// Whether there has been a reset since `time`
+    pub fn since(&self, time: DateTime<Utc>) -> bool {
+        time < self.last(Utc::now())
+    }
+}
+
+/// Format a countdown like `2d 5h 03m 12s`, leaving out the days when there are none
+pub fn format_countdown(duration: Duration) -> String {
+    let time = format!(
+        "{}h {:02}m {:02}s",
+        duration.num_hours() % 24,
+        duration.num_minutes() % 60,
+        duration.num_seconds() % 60
+    );
+    if duration.num_days() > 0 {
+        format!("{}d {}", duration.num_days(), time)
+    } else {
+        time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2021-11-01 is a Monday
+    fn time(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2021, 11, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn daily() {
+        assert_eq!(Reset::Daily.last(time(3, 0, 0)), time(3, 0, 0));
+        assert_eq!(Reset::Daily.next(time(3, 0, 0)), time(4, 0, 0));
+        assert_eq!(Reset::Daily.last(time(3, 23, 59)), time(3, 0, 0));
+        assert_eq!(Reset::Daily.next(time(3, 23, 59)), time(4, 0, 0));
+    }
+
+    #[test]
+    fn daily_rolls_over_the_month() {
+        let end_of_october = Utc.with_ymd_and_hms(2021, 10, 31, 23, 59, 0).unwrap();
+        assert_eq!(
+            Reset::Daily.last(end_of_october),
+            Utc.with_ymd_and_hms(2021, 10, 31, 0, 0, 0).unwrap()
+        );
+        assert_eq!(Reset::Daily.next(end_of_october), time(1, 0, 0));
+    }
+
+    #[test]
+    fn weekly_on_monday() {
+        // Just before the reset, the last one was the Monday before
+        assert_eq!(
+            Reset::Weekly.last(time(1, 7, 29)),
+            Utc.with_ymd_and_hms(2021, 10, 25, 7, 30, 0).unwrap()
+        );
+        assert_eq!(Reset::Weekly.next(time(1, 7, 29)), time(1, 7, 30));
+
+        assert_eq!(Reset::Weekly.last(time(1, 7, 30)), time(1, 7, 30));
+        assert_eq!(Reset::Weekly.next(time(1, 7, 30)), time(8, 7, 30));
+    }
+
+    #[test]
+    fn weekly_on_sunday() {
+        let sunday = time(7, 23, 59);
+        assert_eq!(Reset::Weekly.last(sunday), time(1, 7, 30));
+        assert_eq!(Reset::Weekly.next(sunday), time(8, 7, 30));
+    }
+
+    #[test]
+    fn countdown() {
+        assert_eq!(format_countdown(Duration::seconds(0)), "0h 00m 00s");
+        assert_eq!(format_countdown(Duration::seconds(59)), "0h 00m 59s");
+        assert_eq!(
+            format_countdown(Duration::hours(23) + Duration::minutes(59)),
+            "23h 59m 00s"
+        );
+        assert_eq!(format_countdown(Duration::days(1)), "1d 0h 00m 00s");
+        assert_eq!(
+            format_countdown(
+                Duration::days(2)
+                    + Duration::hours(5)
+                    + Duration::minutes(3)
+                    + Duration::seconds(12)
+            ),
+            "2d 5h 03m 12s"
+        );
+    }
+}
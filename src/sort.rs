@@ -0,0 +1,80 @@
+//! The orders the achievement list can be sorted in
+
+use std::cmp::Ordering;
+
+use crate::api::{AccountAchievement, Achievement};
+
+/// The order achievements are listed in
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum AchievementSort {
+    #[default]
+    Id,
+    Name,
+    Completion,
+    Points,
+    RemainingPoints,
+    NearlyDone,
+}
+
+impl AchievementSort {
+    /// The sort mode that comes after this one when cycling through them
+    pub fn next(&self) -> Self {
+        match self {
+            AchievementSort::Id => AchievementSort::Name,
+            AchievementSort::Name => AchievementSort::Completion,
+            AchievementSort::Completion => AchievementSort::Points,
+            AchievementSort::Points => AchievementSort::RemainingPoints,
+            AchievementSort::RemainingPoints => AchievementSort::NearlyDone,
+            AchievementSort::NearlyDone => AchievementSort::Id,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AchievementSort::Id => "ID",
+            AchievementSort::Name => "name",
+            AchievementSort::Completion => "completion",
+            AchievementSort::Points => "total points",
+            AchievementSort::RemainingPoints => "remaining points",
+            AchievementSort::NearlyDone => "nearly done",
+        }
+    }
+
+    /// Compare two achievements along with their progress on the account. Ties are broken by ID
+    /// so the order is always stable.
+    pub fn compare(
+        &self,
+        (a, a_progress): (&Achievement, Option<&AccountAchievement>),
+        (b, b_progress): (&Achievement, Option<&AccountAchievement>),
+    ) -> Ordering {
+        let progress = |progress: Option<&AccountAchievement>| {
+            progress.map_or(0.0, |account_achievement| account_achievement.progress())
+        };
+        let remaining = |achievement: &Achievement, progress: Option<&AccountAchievement>| {
+            achievement.total_points() - achievement.earned_points(progress)
+        };
+
+        let ordering = match self {
+            AchievementSort::Id => Ordering::Equal,
+            AchievementSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            AchievementSort::Completion => progress(b_progress)
+                .partial_cmp(&progress(a_progress))
+                .unwrap_or(Ordering::Equal),
+            AchievementSort::Points => b.total_points().cmp(&a.total_points()),
+            AchievementSort::RemainingPoints => {
+                remaining(b, b_progress).cmp(&remaining(a, a_progress))
+            }
+            AchievementSort::NearlyDone => {
+                let done = |progress: Option<&AccountAchievement>| {
+                    progress.is_some_and(|account_achievement| account_achievement.done)
+                };
+                done(a_progress).cmp(&done(b_progress)).then_with(|| {
+                    progress(b_progress)
+                        .partial_cmp(&progress(a_progress))
+                        .unwrap_or(Ordering::Equal)
+                })
+            }
+        };
+        ordering.then_with(|| a.id.cmp(&b.id))
+    }
+}
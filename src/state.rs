@@ -1,17 +1,20 @@
 use std::{
     cell::Cell,
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     fs::File,
     io::{BufWriter, Read, Write},
     path::PathBuf,
     sync::RwLock,
 };
 
+use chrono::{DateTime, Utc};
 use log::debug;
 
 use crate::{
     bookmarks::{Bookmark, Bookmarks},
-    tracks::{Track, Tracks},
+    reset::Reset,
+    sort::AchievementSort,
+    tracks::{CustomTrack, Track, Tracks},
 };
 
 #[derive(Serialize, Deserialize, Default)]
@@ -22,6 +25,14 @@ pub struct AppState {
     invalidated: Cell<bool>,
     tracks: RwLock<Tracks>,
     bookmarks: RwLock<Bookmarks>,
+    #[serde(default)]
+    sorting: RwLock<HashMap<String, AchievementSort>>,
+    /// Daily achievements that were marked as done and when
+    #[serde(default)]
+    dailies_done: RwLock<HashMap<usize, DateTime<Utc>>>,
+    /// Names of the map metas to alert about before their events start
+    #[serde(default)]
+    alerts: RwLock<HashSet<String>>,
 }
 
 impl AppState {
@@ -31,6 +42,9 @@ impl AppState {
             invalidated: Cell::new(false),
             tracks: RwLock::new(Tracks::default()),
             bookmarks: RwLock::new(Bookmarks::default()),
+            sorting: RwLock::new(HashMap::default()),
+            dailies_done: RwLock::new(HashMap::default()),
+            alerts: RwLock::new(HashSet::default()),
         }
     }
 
@@ -54,7 +68,7 @@ impl AppState {
 
     pub fn add_bookmark(&self, bookmark: Bookmark) {
         if let Ok(mut bookmarks) = self.bookmarks.write() {
-            if bookmarks.insert(bookmark) {
+            if bookmarks.add(bookmark) {
                 self.invalidated.set(true);
             }
         }
@@ -63,18 +77,42 @@ impl AppState {
 
     pub fn remove_bookmark(&self, bookmark: Bookmark) {
         if let Ok(mut bookmarks) = self.bookmarks.write() {
-            if bookmarks.remove(&bookmark) {
+            if bookmarks.delete(&bookmark) {
                 self.invalidated.set(true);
             }
         }
         self.write_invalid();
     }
 
-    pub fn bookmarks(&self) -> HashSet<Bookmark> {
+    /// Replace the bookmark named `name`, which may have been renamed. Returns false if the new
+    /// name is already taken.
+    pub fn replace_bookmark(&self, name: &str, bookmark: Bookmark) -> bool {
+        let mut replaced = false;
+        if let Ok(mut bookmarks) = self.bookmarks.write() {
+            if bookmarks.replace(name, bookmark) {
+                self.invalidated.set(true);
+                replaced = true;
+            }
+        }
+        self.write_invalid();
+        replaced
+    }
+
+    /// Move a bookmark one place up or down within its folder
+    pub fn shift_bookmark(&self, bookmark: &Bookmark, up: bool) {
+        if let Ok(mut bookmarks) = self.bookmarks.write() {
+            if bookmarks.shift(bookmark, up) {
+                self.invalidated.set(true);
+            }
+        }
+        self.write_invalid();
+    }
+
+    pub fn bookmarks(&self) -> Vec<Bookmark> {
         if let Ok(bookmarks) = self.bookmarks.read() {
             bookmarks.items().clone()
         } else {
-            HashSet::default()
+            Vec::default()
         }
     }
 
@@ -91,6 +129,38 @@ impl AppState {
         self.write_invalid();
     }
 
+    pub fn add_track(&self, track: Track) {
+        if let Ok(mut tracks) = self.tracks.write() {
+            if tracks.insert(track) {
+                self.invalidated.set(true);
+            }
+        }
+        self.write_invalid();
+    }
+
+    /// Replace a track with an edited one. Returns false if the edited one is already tracked.
+    pub fn replace_track(&self, track: &Track, new_track: Track) -> bool {
+        let mut replaced = false;
+        if let Ok(mut tracks) = self.tracks.write() {
+            if tracks.replace(track, new_track) {
+                self.invalidated.set(true);
+                replaced = true;
+            }
+        }
+        self.write_invalid();
+        replaced
+    }
+
+    /// Change the custom track called `name`
+    pub fn update_custom_track(&self, name: &str, update: impl FnOnce(&mut CustomTrack)) {
+        if let Ok(mut tracks) = self.tracks.write() {
+            if tracks.update_custom(name, update) {
+                self.invalidated.set(true);
+            }
+        }
+        self.write_invalid();
+    }
+
     pub fn tracked_items(&self) -> HashSet<Track> {
         if let Ok(tracks) = self.tracks.read() {
             tracks.items().clone()
@@ -107,6 +177,76 @@ impl AppState {
         }
     }
 
+    /// The sort order that was last selected in a view
+    pub fn sorting(&self, view: &str) -> AchievementSort {
+        if let Ok(sorting) = self.sorting.read() {
+            sorting.get(view).copied().unwrap_or_default()
+        } else {
+            AchievementSort::default()
+        }
+    }
+
+    pub fn set_sorting(&self, view: &str, sort: AchievementSort) {
+        if let Ok(mut sorting) = self.sorting.write() {
+            if sorting.insert(view.to_string(), sort) != Some(sort) {
+                self.invalidated.set(true);
+            }
+        }
+        self.write_invalid();
+    }
+
+    /// Whether a daily achievement was marked as done since the last daily reset at 00:00 UTC
+    pub fn is_daily_done(&self, id: usize) -> bool {
+        if let Ok(dailies_done) = self.dailies_done.read() {
+            dailies_done
+                .get(&id)
+                .is_some_and(|marked_at| !Reset::Daily.since(*marked_at))
+        } else {
+            false
+        }
+    }
+
+    pub fn toggle_daily_done(&self, id: usize) {
+        let done = self.is_daily_done(id);
+        if let Ok(mut dailies_done) = self.dailies_done.write() {
+            // Forget anything that was marked before the last reset
+            dailies_done.retain(|_, marked_at| !Reset::Daily.since(*marked_at));
+            if done {
+                dailies_done.remove(&id);
+            } else {
+                dailies_done.insert(id, Utc::now());
+            }
+            self.invalidated.set(true);
+        }
+        self.write_invalid();
+    }
+
+    pub fn toggle_alert(&self, meta: &str) {
+        if let Ok(mut alerts) = self.alerts.write() {
+            if !alerts.remove(meta) {
+                alerts.insert(meta.to_string());
+            }
+            self.invalidated.set(true);
+        }
+        self.write_invalid();
+    }
+
+    pub fn alerts(&self) -> HashSet<String> {
+        if let Ok(alerts) = self.alerts.read() {
+            alerts.clone()
+        } else {
+            HashSet::default()
+        }
+    }
+
+    pub fn has_alert(&self, meta: &str) -> bool {
+        if let Ok(alerts) = self.alerts.read() {
+            alerts.contains(meta)
+        } else {
+            false
+        }
+    }
+
     fn write_invalid(&self) {
         if self.invalidated.get() {
             match self.write() {
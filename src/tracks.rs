@@ -1,8 +1,18 @@
 use std::{
     collections::{hash_set::IntoIter, HashSet},
+    fmt,
+    hash::Hash,
     ops::{Deref, DerefMut},
 };
 
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{
+    de::{self, MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+use crate::reset::Reset;
+
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Tracks(HashSet<Track>);
 
@@ -29,33 +39,395 @@ impl DerefMut for Tracks {
     }
 }
 
-#[derive(Serialize, Deserialize, Hash, Eq, Clone, Debug)]
+#[derive(Serialize, Deserialize, Hash, PartialEq, Eq, Clone, Debug)]
 pub enum Track {
     Achievement(usize),
-    Custom(String),
+    Custom(#[serde(deserialize_with = "deserialize_custom")] CustomTrack),
 }
 
-impl PartialEq for Track {
-    fn eq(&self, other: &Self) -> bool {
+/// Something to do that isn't an achievement
+#[derive(Serialize, Deserialize, Eq, Clone, Debug, Default)]
+pub struct CustomTrack {
+    pub name: String,
+    /// When the track was marked as done
+    #[serde(default)]
+    pub done_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItem>,
+    #[serde(default)]
+    pub progress: Option<Progress>,
+    #[serde(default)]
+    pub due: Option<NaiveDate>,
+    /// How often the track starts over
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    /// When the track was last changed, to know whether it has been reset since
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct ChecklistItem {
+    pub name: String,
+    pub done: bool,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Progress {
+    pub current: usize,
+    pub max: usize,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Recurrence {
+    /// Starts over at the daily reset at 00:00 UTC
+    Daily,
+    /// Starts over at the weekly reset on Monday at 07:30 UTC
+    Weekly,
+}
+
+impl Recurrence {
+    pub fn reset(&self) -> Reset {
         match self {
-            Track::Achievement(id) => {
-                if let Track::Achievement(other_id) = other {
-                    return id == other_id;
-                }
-                return false;
-            }
-            Track::Custom(content) => {
-                if let Track::Custom(other_content) = other {
-                    return content == other_content;
-                }
-                return false;
+            Recurrence::Daily => Reset::Daily,
+            Recurrence::Weekly => Reset::Weekly,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Recurrence::Daily => "Daily",
+            Recurrence::Weekly => "Weekly",
+        }
+    }
+}
+
+impl CustomTrack {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            ..Default::default()
+        }
+    }
+
+    /// Whether the track has started over since it was last changed
+    fn expired(&self) -> bool {
+        match (self.recurrence, self.updated_at) {
+            (Some(recurrence), Some(updated_at)) => recurrence.reset().since(updated_at),
+            _ => false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        !self.expired() && self.done_at.is_some()
+    }
+
+    pub fn is_checked(&self, index: usize) -> bool {
+        !self.expired() && self.checklist.get(index).is_some_and(|item| item.done)
+    }
+
+    pub fn current(&self) -> usize {
+        match self.progress {
+            Some(progress) if !self.expired() => progress.current,
+            _ => 0,
+        }
+    }
+
+    /// How far along the track is, from `0.0` to `1.0`. A track that is done is complete,
+    /// otherwise the progress or the checked items count towards it.
+    pub fn ratio(&self) -> Option<f64> {
+        if self.is_done() {
+            return Some(1.0);
+        }
+        if let Some(progress) = self.progress.filter(|progress| progress.max > 0) {
+            return Some(self.current().min(progress.max) as f64 / progress.max as f64);
+        }
+        if !self.checklist.is_empty() {
+            let checked = (0..self.checklist.len())
+                .filter(|index| self.is_checked(*index))
+                .count();
+            return Some(checked as f64 / self.checklist.len() as f64);
+        }
+        None
+    }
+
+    /// Whether the due date has passed without the track being done
+    pub fn is_overdue(&self) -> bool {
+        !self.is_done()
+            && self
+                .due
+                .is_some_and(|due| due < Utc::now().naive_utc().date())
+    }
+
+    // Clear everything that was done before the last reset, before changing anything
+    fn touch(&mut self) {
+        if self.expired() {
+            self.done_at = None;
+            self.checklist.iter_mut().for_each(|item| item.done = false);
+            if let Some(progress) = self.progress.as_mut() {
+                progress.current = 0;
             }
         }
+        self.updated_at = Some(Utc::now());
+    }
+
+    pub fn toggle_done(&mut self) {
+        self.touch();
+        self.done_at = match self.done_at {
+            Some(_) => None,
+            None => Some(Utc::now()),
+        };
+    }
+
+    pub fn toggle_checked(&mut self, index: usize) {
+        self.touch();
+        if let Some(item) = self.checklist.get_mut(index) {
+            item.done = !item.done;
+        }
+    }
+
+    /// Change the progress by `amount`, staying between 0 and the maximum
+    pub fn add_progress(&mut self, amount: i64) {
+        self.touch();
+        if let Some(progress) = self.progress.as_mut() {
+            progress.current =
+                (progress.current as i64 + amount).clamp(0, progress.max as i64) as usize;
+        }
     }
 }
 
+impl PartialEq for CustomTrack {
+    fn eq(&self, other: &Self) -> bool {
+        self.name.eq(&other.name)
+    }
+}
+
+impl Hash for CustomTrack {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.name.hash(state)
+    }
+}
+
+// Custom tracks used to be saved as just their name
+fn deserialize_custom<'de, D: Deserializer<'de>>(deserializer: D) -> Result<CustomTrack, D::Error> {
+    struct CustomTrackVisitor;
+
+    impl<'de> Visitor<'de> for CustomTrackVisitor {
+        type Value = CustomTrack;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a custom track or its name")
+        }
+
+        fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+            Ok(CustomTrack::new(name.to_string()))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+            CustomTrack::deserialize(de::value::MapAccessDeserializer::new(map))
+        }
+    }
+
+    deserializer.deserialize_any(CustomTrackVisitor)
+}
+
 impl Tracks {
     pub fn items(&self) -> &HashSet<Track> {
         &self.0
     }
+
+    /// Change a custom track. Returns false if there's no custom track with that name.
+    pub fn update_custom(&mut self, name: &str, update: impl FnOnce(&mut CustomTrack)) -> bool {
+        let mut track = match self
+            .0
+            .take(&Track::Custom(CustomTrack::new(name.to_string())))
+        {
+            Some(Track::Custom(track)) => track,
+            Some(track) => {
+                self.0.insert(track);
+                return false;
+            }
+            None => return false,
+        };
+        update(&mut track);
+        self.0.insert(Track::Custom(track))
+    }
+
+    /// Replace a track with another. Returns false if it isn't tracked, or if the new one
+    /// already is.
+    pub fn replace(&mut self, track: &Track, new_track: Track) -> bool {
+        if !self.0.contains(track) || (track != &new_track && self.0.contains(&new_track)) {
+            return false;
+        }
+        self.0.remove(track);
+        self.0.insert(new_track)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn sample_track() -> CustomTrack {
+        CustomTrack {
+            checklist: vec![
+                ChecklistItem {
+                    name: "Fractals".to_string(),
+                    done: true,
+                },
+                ChecklistItem {
+                    name: "Strikes".to_string(),
+                    done: false,
+                },
+            ],
+            progress: Some(Progress { current: 3, max: 4 }),
+            done_at: Some(Utc::now()),
+            updated_at: Some(Utc::now()),
+            ..CustomTrack::new("Dailies".to_string())
+        }
+    }
+
+    // A daily track that was last changed before the previous reset
+    fn expired_track() -> CustomTrack {
+        CustomTrack {
+            recurrence: Some(Recurrence::Daily),
+            updated_at: Some(Utc::now() - Duration::days(2)),
+            ..sample_track()
+        }
+    }
+
+    #[test]
+    fn legacy_format() {
+        let tracks =
+            ron::from_str::<Tracks>(r#"([Custom("Map completion"), Achievement(1234)])"#).unwrap();
+        assert_eq!(tracks.len(), 2);
+        assert!(tracks.contains(&Track::Achievement(1234)));
+        let custom = tracks
+            .iter()
+            .find_map(|track| match track {
+                Track::Custom(custom) => Some(custom),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(custom.name, "Map completion");
+        assert_eq!(custom.done_at, None);
+        assert!(custom.checklist.is_empty());
+        assert_eq!(custom.progress, None);
+        assert_eq!(custom.recurrence, None);
+    }
+
+    #[test]
+    fn round_trip() {
+        let track = CustomTrack {
+            due: NaiveDate::from_ymd_opt(2021, 12, 31),
+            recurrence: Some(Recurrence::Weekly),
+            ..sample_track()
+        };
+        let mut tracks = Tracks::default();
+        tracks.insert(Track::Custom(track.clone()));
+
+        let saved = ron::to_string(&tracks).unwrap();
+        let loaded = ron::from_str::<Tracks>(&saved).unwrap();
+        match loaded.iter().next() {
+            Some(Track::Custom(loaded)) => {
+                assert_eq!(loaded.name, track.name);
+                assert_eq!(
+                    loaded.done_at.map(|time| time.timestamp()),
+                    track.done_at.map(|time| time.timestamp())
+                );
+                assert_eq!(loaded.checklist, track.checklist);
+                assert_eq!(loaded.progress, track.progress);
+                assert_eq!(loaded.due, track.due);
+                assert_eq!(loaded.recurrence, track.recurrence);
+                assert_eq!(
+                    loaded.updated_at.map(|time| time.timestamp()),
+                    track.updated_at.map(|time| time.timestamp())
+                );
+            }
+            other => panic!("expected a custom track, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn expiry() {
+        let track = sample_track();
+        assert!(track.is_done());
+        assert!(track.is_checked(0));
+        assert_eq!(track.current(), 3);
+
+        // Tracks that don't repeat are never reset
+        let track = CustomTrack {
+            updated_at: Some(Utc::now() - Duration::days(30)),
+            ..track
+        };
+        assert!(track.is_done());
+
+        let track = expired_track();
+        assert!(!track.is_done());
+        assert!(!track.is_checked(0));
+        assert_eq!(track.current(), 0);
+        assert_eq!(track.ratio(), Some(0.0));
+    }
+
+    #[test]
+    fn touch_clears_expired_progress() {
+        let mut track = expired_track();
+        track.toggle_checked(1);
+        assert_eq!(track.done_at, None);
+        assert!(!track.is_checked(0));
+        assert!(track.is_checked(1));
+        assert_eq!(track.progress, Some(Progress { current: 0, max: 4 }));
+        assert!(track.updated_at.unwrap() > Utc::now() - Duration::minutes(1));
+
+        // Nothing is cleared when the track hasn't been reset
+        let mut track = sample_track();
+        track.toggle_checked(1);
+        assert!(track.is_done());
+        assert!(track.is_checked(0));
+        assert_eq!(track.current(), 3);
+    }
+
+    #[test]
+    fn ratio() {
+        assert_eq!(sample_track().ratio(), Some(1.0));
+
+        let track = CustomTrack {
+            done_at: None,
+            ..sample_track()
+        };
+        assert_eq!(track.ratio(), Some(0.75));
+
+        // Without a goal, the checked items count instead
+        let track = CustomTrack {
+            progress: Some(Progress { current: 0, max: 0 }),
+            ..track
+        };
+        assert_eq!(track.ratio(), Some(0.5));
+
+        let track = CustomTrack {
+            checklist: vec![],
+            progress: None,
+            ..track
+        };
+        assert_eq!(track.ratio(), None);
+    }
+
+    #[test]
+    fn add_progress_is_clamped() {
+        let mut track = sample_track();
+        track.add_progress(1);
+        assert_eq!(track.current(), 4);
+        track.add_progress(10);
+        assert_eq!(track.current(), 4);
+        track.add_progress(-2);
+        assert_eq!(track.current(), 2);
+        track.add_progress(-10);
+        assert_eq!(track.current(), 0);
+
+        let mut track = CustomTrack::new("No goal".to_string());
+        track.add_progress(1);
+        assert_eq!(track.progress, None);
+    }
 }
@@ -0,0 +1,81 @@
+use std::{collections::HashSet, rc::Rc};
+
+use chrono::{Duration, Timelike, Utc};
+use gw2timers::meta::MapMetaKind;
+use tokio::sync::mpsc::UnboundedSender;
+
+use orrient::{events::Event, state::AppState};
+
+/// Sends an [Event::Alert] shortly before the events of subscribed map metas start
+pub struct Alerts {
+    app_state: Rc<AppState>,
+    tx_event: UnboundedSender<Event>,
+    lead_time: Duration,
+    /// Event name and start time (in seconds since the epoch) of the events already alerted
+    alerted: HashSet<(&'static str, i64)>,
+}
+
+impl Alerts {
+    pub fn new(
+        app_state: Rc<AppState>,
+        tx_event: UnboundedSender<Event>,
+        lead_time: Duration,
+    ) -> Self {
+        Self {
+            app_state,
+            tx_event,
+            lead_time,
+            alerted: HashSet::default(),
+        }
+    }
+
+    pub async fn run(mut self) {
+        let mut tick = tokio::time::interval(std::time::Duration::from_secs(1));
+        loop {
+            tick.tick().await;
+            self.check();
+        }
+    }
+
+    fn check(&mut self) {
+        let subscriptions = self.app_state.alerts();
+        if subscriptions.is_empty() {
+            return;
+        }
+
+        let lead_time = self.lead_time;
+        let now = Utc::now();
+        let time = Duration::seconds(now.time().num_seconds_from_midnight() as i64);
+        self.alerted
+            .retain(|(_, start_time)| *start_time >= now.timestamp());
+
+        for meta_key in MapMetaKind::all_keys() {
+            let meta = meta_key.info();
+            if !subscriptions.contains(meta.name) {
+                continue;
+            }
+
+            let upcoming = meta_key
+                .into_iter()
+                .fast_forward(time)
+                .take_while(|event_instance| event_instance.start_time - time <= lead_time)
+                .filter(|event_instance| event_instance.start_time > time);
+
+            for event_instance in upcoming {
+                let time_until = event_instance.start_time - time;
+                let start_time = (now + time_until).timestamp();
+                if self
+                    .alerted
+                    .insert((event_instance.schedule.name, start_time))
+                {
+                    let _ = self.tx_event.send(Event::Alert(format!(
+                        "{} ({}) starts in {}m",
+                        event_instance.schedule.name,
+                        meta.name,
+                        (time_until.num_seconds() + 59) / 60
+                    )));
+                }
+            }
+        }
+    }
+}
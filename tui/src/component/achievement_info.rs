@@ -3,10 +3,16 @@ use std::cmp::min;
 use tui::{
     layout::Constraint,
     style::{Modifier, Style},
-    widgets::{Row, Table},
+    text::{Span, Spans},
+    widgets::{Cell, Row, Table},
 };
 
-use orrient::api::{AccountAchievement, Achievement, Reward};
+use orrient::{
+    api::{AccountAchievement, Achievement, Reward},
+    events::Rewards,
+};
+
+use super::{coins::Coins, item_info::ItemInfo};
 
 pub struct AchievementInfo;
 
@@ -14,6 +20,7 @@ impl AchievementInfo {
     pub fn new_widget<'a>(
         achievement: &'a Achievement,
         account_achievement: Option<&'a AccountAchievement>,
+        rewards: &'a Rewards,
     ) -> Table<'a> {
         let mut rows = vec![];
         rows.append(&mut vec![
@@ -26,7 +33,7 @@ impl AchievementInfo {
             Self::flags_row(achievement),
             Self::tiers_row(achievement),
             Self::prerequirsites_row(achievement),
-            Self::rewards_row(achievement),
+            Self::rewards_row(achievement, rewards),
             Self::bits_row(achievement),
             Self::point_cap_row(achievement),
         ]);
@@ -43,15 +50,15 @@ impl AchievementInfo {
         Row::new(vec![""])
     }
 
-    fn custom_row<'a>(msg: &'a str) -> Row<'static> {
+    fn custom_row(msg: &str) -> Row<'static> {
         Row::new(vec![String::from(msg)]).style(Style::default().add_modifier(Modifier::BOLD))
     }
 
-    fn id_row(achievement: &Achievement) -> Row {
+    fn id_row(achievement: &Achievement) -> Row<'_> {
         Row::new(vec!["ID".to_string(), achievement.id.to_string()])
     }
 
-    fn icon_row(achievement: &Achievement) -> Row {
+    fn icon_row(achievement: &Achievement) -> Row<'_> {
         Row::new(vec![
             "Icon".to_string(),
             achievement
@@ -61,39 +68,39 @@ impl AchievementInfo {
         ])
     }
 
-    fn description_row(achievement: &Achievement) -> Row {
+    fn description_row(achievement: &Achievement) -> Row<'_> {
         Row::new(vec![
             "Description".to_string(),
             achievement.description.to_string(),
         ])
     }
 
-    fn requirement_row(achievement: &Achievement) -> Row {
+    fn requirement_row(achievement: &Achievement) -> Row<'_> {
         Row::new(vec![
             "Requirement".to_string(),
             achievement.requirement.to_string(),
         ])
     }
 
-    fn locked_text_row(achievement: &Achievement) -> Row {
+    fn locked_text_row(achievement: &Achievement) -> Row<'_> {
         Row::new(vec![
             "Locked Text".to_string(),
             achievement.locked_text.to_string(),
         ])
     }
 
-    fn type_row(achievement: &Achievement) -> Row {
+    fn type_row(achievement: &Achievement) -> Row<'_> {
         Row::new(vec![
             "Type".to_string(),
             achievement.achievement_type.to_string(),
         ])
     }
 
-    fn flags_row(achievement: &Achievement) -> Row {
+    fn flags_row(achievement: &Achievement) -> Row<'_> {
         Row::new(vec!["Flags".to_string(), achievement.flags.join(", ")])
     }
 
-    fn tiers_row(achievement: &Achievement) -> Row {
+    fn tiers_row(achievement: &Achievement) -> Row<'_> {
         Row::new(vec![
             "Tiers".to_string(),
             achievement
@@ -105,7 +112,7 @@ impl AchievementInfo {
         ])
     }
 
-    fn prerequirsites_row(achievement: &Achievement) -> Row {
+    fn prerequirsites_row(achievement: &Achievement) -> Row<'_> {
         Row::new(vec![
             "Prerequisites".to_string(),
             achievement
@@ -121,30 +128,44 @@ impl AchievementInfo {
         ])
     }
 
-    fn rewards_row(achievement: &Achievement) -> Row {
-        Row::new(vec![
-            "Rewards".to_string(),
-            achievement
-                .rewards
-                .as_ref()
-                .map_or("None".to_string(), |rewards| {
-                    rewards
-                        .iter()
-                        .map(|reward| match reward {
-                            Reward::Coins { count } => format!("{} coins", count),
-                            Reward::Item { id, count } => format!("{}x Item {}", count, id),
-                            Reward::Mastery { id, region } => {
-                                format!("{} Mastery id: {}", region, id)
-                            }
-                            Reward::Title { id } => format!("Title id {}", id),
-                        })
-                        .collect::<Vec<String>>()
-                        .join(", ")
-                }),
-        ])
+    fn rewards_row<'a>(achievement: &'a Achievement, rewards: &'a Rewards) -> Row<'a> {
+        let spans = match &achievement.rewards {
+            Some(achievement_rewards) => achievement_rewards
+                .iter()
+                .enumerate()
+                .flat_map(|(index, reward)| {
+                    let separator = if index == 0 { "" } else { ", " };
+                    vec![Span::raw(separator), Self::reward_span(reward, rewards)]
+                })
+                .collect::<Vec<Span>>(),
+            None => vec![Span::raw("None")],
+        };
+        Row::new(vec![Cell::from("Rewards"), Cell::from(Spans::from(spans))])
+    }
+
+    // Names the reward if it has been loaded, otherwise falls back to its ID
+    fn reward_span<'a>(reward: &Reward, rewards: &'a Rewards) -> Span<'a> {
+        match reward {
+            Reward::Coins { count } => Span::raw(Coins::format(*count)),
+            Reward::Item { id, count } => match rewards.items.get(id) {
+                Some(item) => Span::styled(
+                    format!("{}x {}", count, item.name),
+                    Style::default().fg(ItemInfo::rarity_color(&item.rarity)),
+                ),
+                None => Span::raw(format!("{}x Item {}", count, id)),
+            },
+            Reward::Mastery { id, region } => match rewards.masteries.get(id) {
+                Some(mastery) => Span::raw(format!("{} Mastery ({})", mastery.name, region)),
+                None => Span::raw(format!("{} Mastery id: {}", region, id)),
+            },
+            Reward::Title { id } => match rewards.titles.get(id) {
+                Some(title) => Span::raw(format!("Title \"{}\"", title.name)),
+                None => Span::raw(format!("Title id {}", id)),
+            },
+        }
     }
 
-    fn bits_row(achievement: &Achievement) -> Row {
+    fn bits_row(achievement: &Achievement) -> Row<'_> {
         Row::new(vec![
             "Bits".to_string(),
             achievement
@@ -159,7 +180,7 @@ impl AchievementInfo {
         ])
     }
 
-    fn point_cap_row(achievement: &Achievement) -> Row {
+    fn point_cap_row(achievement: &Achievement) -> Row<'_> {
         Row::new(vec![
             "Point Cap".to_string(),
             achievement
@@ -24,19 +24,27 @@ impl AchievementProgressInfo {
                         min(account_achievement.current.unwrap_or_default(), tier.count) as f64;
                     let total: f64 = tier.count as f64;
                     let ratio = current_progress / total;
-                    Gauge::default()
-                        .label(format!(
+                    Self::gauge(
+                        format!(
                             "Tier {} ({} AP): {}%",
                             i + 1,
                             tier.points,
                             ((ratio * 100f64) as u16)
-                        ))
-                        .ratio(ratio)
-                        .gauge_style(Style::default().fg(Color::Gray).bg(Color::Black))
+                        ),
+                        ratio,
+                    )
                 })
                 .collect::<Vec<Gauge>>()
         } else {
             vec![]
         }
     }
+
+    /// A progress bar in the style used for achievement tiers
+    pub fn gauge<'a>(label: String, ratio: f64) -> Gauge<'a> {
+        Gauge::default()
+            .label(label)
+            .ratio(ratio)
+            .gauge_style(Style::default().fg(Color::Gray).bg(Color::Black))
+    }
 }
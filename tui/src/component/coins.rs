@@ -0,0 +1,16 @@
+pub struct Coins;
+
+impl Coins {
+    /// Format an amount of copper coins as gold, silver and copper. For example, `10250` is
+    /// `1g 02s 50c`
+    pub fn format(copper: usize) -> String {
+        let (gold, silver, copper) = (copper / 10000, copper / 100 % 100, copper % 100);
+        if gold > 0 {
+            format!("{}g {:02}s {:02}c", gold, silver, copper)
+        } else if silver > 0 {
+            format!("{}s {:02}c", silver, copper)
+        } else {
+            format!("{}c", copper)
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use tui::{
+    layout::Constraint,
+    style::{Color, Modifier, Style},
+    widgets::{Cell, Row, Table},
+};
+
+use orrient::{
+    api::{Item, Recipe},
+    events::ItemDetails,
+};
+
+use super::coins::Coins;
+
+pub struct ItemInfo;
+
+impl ItemInfo {
+    pub fn new_widget<'a>(
+        item: &'a Item,
+        details: Option<&'a ItemDetails>,
+        items: &'a HashMap<usize, Item>,
+    ) -> Table<'a> {
+        let mut rows = vec![
+            Self::name_row(item),
+            Row::new(vec!["ID".to_string(), item.id.to_string()]),
+            Row::new(vec!["Type".to_string(), item.item_type.clone()]),
+            Row::new(vec!["Rarity".to_string(), item.rarity.clone()]),
+            Row::new(vec!["Level".to_string(), item.level.to_string()]),
+            Row::new(vec![
+                "Vendor Value".to_string(),
+                Coins::format(item.vendor_value),
+            ]),
+            Row::new(vec!["Chat Link".to_string(), item.chat_link.clone()]),
+            Row::new(vec![
+                "Description".to_string(),
+                item.description.clone().unwrap_or_default(),
+            ]),
+            Self::empty_row(),
+            Self::custom_row("Trading Post"),
+        ];
+
+        match details {
+            Some(details) => {
+                rows.append(&mut Self::price_rows(details));
+                rows.push(Self::empty_row());
+                rows.push(Self::custom_row("Crafted From"));
+                rows.append(&mut Self::produced_by_rows(&details.produced_by, items));
+                rows.push(Self::empty_row());
+                rows.push(Self::custom_row("Used In"));
+                rows.append(&mut Self::used_in_rows(&details.used_in, items));
+            }
+            None => rows.push(Row::new(vec!["Loading...".to_string()])),
+        }
+
+        Table::new(rows).widths(&[Constraint::Min(15), Constraint::Min(100)])
+    }
+
+    /// The color the game uses for the name of an item of a rarity
+    pub fn rarity_color(rarity: &str) -> Color {
+        match rarity {
+            "Junk" => Color::DarkGray,
+            "Fine" => Color::Blue,
+            "Masterwork" => Color::Green,
+            "Rare" => Color::Yellow,
+            "Exotic" => Color::Rgb(255, 164, 5),
+            "Ascended" => Color::Magenta,
+            "Legendary" => Color::LightMagenta,
+            _ => Color::White,
+        }
+    }
+
+    fn empty_row() -> Row<'static> {
+        Row::new(vec![""])
+    }
+
+    fn custom_row(msg: &str) -> Row<'static> {
+        Row::new(vec![String::from(msg)]).style(Style::default().add_modifier(Modifier::BOLD))
+    }
+
+    fn name_row(item: &Item) -> Row<'_> {
+        Row::new(vec![
+            Cell::from("Name"),
+            Cell::from(item.name.as_str()).style(
+                Style::default()
+                    .fg(Self::rarity_color(&item.rarity))
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ])
+    }
+
+    fn price_rows(details: &ItemDetails) -> Vec<Row<'static>> {
+        match &details.price {
+            Some(price) => vec![
+                Row::new(vec![
+                    "Buy".to_string(),
+                    format!(
+                        "{} ({} ordered)",
+                        Coins::format(price.buys.unit_price),
+                        price.buys.quantity
+                    ),
+                ]),
+                Row::new(vec![
+                    "Sell".to_string(),
+                    format!(
+                        "{} ({} listed)",
+                        Coins::format(price.sells.unit_price),
+                        price.sells.quantity
+                    ),
+                ]),
+            ],
+            None => vec![Row::new(vec!["Not tradable".to_string()])],
+        }
+    }
+
+    fn produced_by_rows(recipes: &[Recipe], items: &HashMap<usize, Item>) -> Vec<Row<'static>> {
+        if recipes.is_empty() {
+            return vec![Row::new(vec!["None".to_string()])];
+        }
+
+        recipes
+            .iter()
+            .map(|recipe| {
+                Row::new(vec![
+                    Self::disciplines(recipe),
+                    recipe
+                        .ingredients
+                        .iter()
+                        .map(|ingredient| {
+                            format!(
+                                "{}x {}",
+                                ingredient.count,
+                                Self::item_name(ingredient.item_id, items)
+                            )
+                        })
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                ])
+            })
+            .collect()
+    }
+
+    fn used_in_rows(recipes: &[Recipe], items: &HashMap<usize, Item>) -> Vec<Row<'static>> {
+        if recipes.is_empty() {
+            return vec![Row::new(vec!["None".to_string()])];
+        }
+
+        recipes
+            .iter()
+            .map(|recipe| {
+                Row::new(vec![
+                    Self::disciplines(recipe),
+                    format!(
+                        "{}x {}",
+                        recipe.output_item_count,
+                        Self::item_name(recipe.output_item_id, items)
+                    ),
+                ])
+            })
+            .collect()
+    }
+
+    fn disciplines(recipe: &Recipe) -> String {
+        format!("{} {}", recipe.disciplines.join("/"), recipe.min_rating)
+    }
+
+    fn item_name(id: usize, items: &HashMap<usize, Item>) -> String {
+        items
+            .get(&id)
+            .map_or(format!("Item {}", id), |item| item.name.clone())
+    }
+}
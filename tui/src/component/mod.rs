@@ -1,3 +1,5 @@
 // A component is a part of the UI with a specific purpose
 pub mod achievement_info;
 pub mod achievement_progress_info;
+pub mod coins;
+pub mod item_info;
@@ -11,6 +11,9 @@ pub enum InputKind {
     MoveRight(u16),
     MoveUp(u16),
     MoveDown(u16),
+    /// Move the selected item itself up, such as reordering a list
+    ShiftUp,
+    ShiftDown,
     Top,
     Bottom,
     Confirm,
@@ -19,7 +22,11 @@ pub enum InputKind {
     Back,
     Quit,
     New,
+    Edit,
     Search,
+    Browse,
+    Sort,
+    Filter,
     SwitchTab(usize),
     Unhandled,
 }
@@ -59,6 +66,8 @@ impl Input {
                 KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('d') => InputKind::MoveRight(1),
                 KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('w') => InputKind::MoveUp(1),
                 KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('s') => InputKind::MoveDown(1),
+                KeyCode::Char('K') => InputKind::ShiftUp,
+                KeyCode::Char('J') => InputKind::ShiftDown,
                 KeyCode::PageUp => InputKind::MoveUp(100),
                 KeyCode::PageDown => InputKind::MoveDown(100),
                 KeyCode::Esc => InputKind::Back,
@@ -67,14 +76,19 @@ impl Input {
                 KeyCode::Enter => InputKind::Confirm,
                 KeyCode::Char(' ') => InputKind::Select,
                 KeyCode::Char('n') => InputKind::New,
+                KeyCode::Char('e') => InputKind::Edit,
                 KeyCode::Char('x') => InputKind::Delete,
                 KeyCode::Char('q') => InputKind::Quit,
                 KeyCode::Char('/') => InputKind::Search,
+                KeyCode::Char('b') => InputKind::Browse,
+                KeyCode::Char('o') => InputKind::Sort,
+                KeyCode::Char('f') => InputKind::Filter,
                 KeyCode::Char('1') => InputKind::SwitchTab(0),
                 KeyCode::Char('2') => InputKind::SwitchTab(1),
                 KeyCode::Char('3') => InputKind::SwitchTab(2),
                 KeyCode::Char('4') => InputKind::SwitchTab(3),
                 KeyCode::Char('5') => InputKind::SwitchTab(4),
+                KeyCode::Char('6') => InputKind::SwitchTab(5),
                 _ => InputKind::Unhandled,
             };
 
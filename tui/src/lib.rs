@@ -1,3 +1,4 @@
+pub mod alerts;
 pub mod component;
 mod input;
 mod view;
@@ -9,6 +10,7 @@ extern crate tui;
 use std::{
     io::{self, Stdout},
     rc::Rc,
+    time::Duration,
 };
 
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
@@ -26,11 +28,11 @@ use tui::{
 };
 
 use crate::input::{Input, InputEvent, InputKind};
-use orrient::{config::Config, events::Event, state::AppState};
+use orrient::{config::Config, events::Event, fetch::Request, state::AppState};
 
 use self::view::{
     achievements::AchievementsView, bookmarks::BookmarksView, dailies::DailiesView,
-    status::StatusView, timer::TimerView, tracks::TracksView, View,
+    items::ItemsView, status::StatusView, timer::TimerView, tracks::TracksView, View,
 };
 
 pub struct UI {
@@ -49,13 +51,15 @@ impl UI {
         app_state: Rc<AppState>,
         tx_event: UnboundedSender<Event>,
         rx_event: UnboundedReceiver<Event>,
+        tx_request: UnboundedSender<Request>,
     ) -> Self {
         let tabs = vec![
             Box::new(TracksView::new(app_state.clone(), tx_event.clone())) as Box<dyn View>,
             Box::new(AchievementsView::new(app_state.clone(), tx_event.clone())),
-            Box::new(DailiesView::new()),
-            Box::new(TimerView::new()),
+            Box::new(DailiesView::new(app_state.clone())),
+            Box::new(TimerView::new(app_state.clone(), tx_event.clone())),
             Box::new(BookmarksView::new(app_state.clone(), tx_event.clone())),
+            Box::new(ItemsView::new(tx_request)),
         ];
         let tab_names = tabs.iter().map(|tab| tab.name()).collect::<Vec<_>>();
 
@@ -87,6 +91,9 @@ impl UI {
         let input = Input::new(tx_input.clone());
         tokio::spawn(input.run());
 
+        // Redraw every second so countdowns and timers stay up to date
+        let mut render_tick = tokio::time::interval(Duration::from_secs(1));
+
         // Render loop
         loop {
             if self.quit {
@@ -101,6 +108,9 @@ impl UI {
                     self.handle_event(view_event);
                     self.render(&mut terminal);
                 }
+                _ = render_tick.tick() => {
+                    self.render(&mut terminal);
+                }
             }
         }
 
@@ -114,12 +124,18 @@ impl UI {
         }
     }
 
+    fn select_tab_by_name(&mut self, name: &str) {
+        if let Some(tab_index) = self.tabs.iter().position(|tab| tab.name() == name) {
+            self.current_tab = tab_index;
+        }
+    }
+
     pub fn handle_input(&mut self, input_event: InputEvent) {
         // Pass input events to current view
         if !self
             .tabs
             .get_mut(self.current_tab)
-            .map_or(false, |tab| tab.handle_input(&input_event))
+            .is_some_and(|tab| tab.handle_input(&input_event))
         {
             // If view doesn't handle input, handle it locally
             match input_event.input {
@@ -133,7 +149,11 @@ impl UI {
     pub fn handle_event(&mut self, event: Event) {
         match &event {
             Event::Quit => self.quit = true,
+            // Tracks added from the UI have to be saved here, otherwise they're lost on exit
+            Event::AddTrack(track) => self.app_state.add_track(track.clone()),
             Event::ToggleTrack(track) => self.app_state.toggle_track(track),
+            Event::ShowItem(_) => self.select_tab_by_name("Items"),
+            Event::ShowAchievement(_) => self.select_tab_by_name("Achievements"),
             _ => {}
         }
         self.status_view.handle_event(&event);
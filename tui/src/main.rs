@@ -8,11 +8,11 @@ use orrient::{
     client::{self, CachedClient},
     config::{self, Config},
     events::Event,
-    fetch::Fetch,
+    fetch::{Fetch, Request},
     log::setup_logger,
     state::AppState,
 };
-use orrient_tui::UI;
+use orrient_tui::{alerts::Alerts, UI};
 use signals::handle_signals;
 
 use signal_hook::consts::{SIGINT, SIGQUIT, SIGTERM};
@@ -46,19 +46,29 @@ pub async fn main() -> Result {
     debug!("{:?}", config);
 
     let (tx_event, rx_event) = mpsc::unbounded_channel::<Event>();
+    let (tx_request, rx_request) = mpsc::unbounded_channel::<Request>();
 
     let app_state = Rc::new(AppState::load("state.ron"));
-    let ui = UI::new(&config, app_state.clone(), tx_event.clone(), rx_event);
+    let ui = UI::new(
+        &config,
+        app_state.clone(),
+        tx_event.clone(),
+        rx_event,
+        tx_request,
+    );
+
+    let alerts = Alerts::new(app_state.clone(), tx_event.clone(), config.alert_lead_time);
 
     let client = CachedClient::new(config).map_err(Error::Client)?;
-    let fetch = Fetch::new(client, tx_event.clone());
+    let fetch = Fetch::new(client, tx_event.clone(), rx_request);
 
-    let signals = SignalsInfo::new(&[SIGTERM, SIGINT, SIGQUIT]).map_err(Error::Signal)?;
+    let signals = SignalsInfo::new([SIGTERM, SIGINT, SIGQUIT]).map_err(Error::Signal)?;
 
     select! {
         _ = handle_signals(signals, tx_event) => {}
         _ = fetch.run(60) => {}
         _ = ui.run() => {}
+        _ = alerts.run() => {}
     }
     Ok(())
 }
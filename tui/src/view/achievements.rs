@@ -8,14 +8,16 @@ use crate::{
 };
 
 use orrient::{
-    api::{AccountAchievement, Achievement},
-    events::Event,
+    api::{AccountAchievement, Achievement, AchievementCategory, AchievementGroup},
+    events::{Event, Rewards},
+    query::{Query, Subject},
+    sort::AchievementSort,
     state::AppState,
     tracks::Track,
 };
 
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     io::Stdout,
     rc::Rc,
 };
@@ -25,7 +27,7 @@ use tui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::Text,
+    text::{Span, Spans, Text},
     widgets::{Block, Borders, List, ListItem, ListState},
     Frame,
 };
@@ -40,20 +42,41 @@ struct AchievementStatusStyles {
     pub tracked: Style,
 }
 
+/// A row in the group -> category -> achievement browser
+#[derive(Clone, Debug, PartialEq)]
+enum TreeNode {
+    Group(String),
+    Category(usize),
+    Achievement(usize),
+}
+
+const VIEW_NAME: &str = "Achievements";
+
 pub struct AchievementsView {
     app_state: Rc<AppState>,
     list_state: ListState,
     textbox_state: TextboxState,
     achievements: BTreeMap<usize, Achievement>,
     account_achievements: HashMap<usize, AccountAchievement>,
+    groups: Vec<AchievementGroup>,
+    categories: HashMap<usize, AchievementCategory>,
+    rewards: Rewards,
+    expanded_groups: HashSet<String>,
+    expanded_categories: HashSet<usize>,
     tx_event: UnboundedSender<Event>,
     visible_list_ids: Vec<usize>,
+    visible_tree_nodes: Vec<TreeNode>,
+    /// The last search that couldn't be parsed, so its error is only reported once
+    invalid_query: Option<String>,
     searching: bool,
+    browsing: bool,
+    sort: AchievementSort,
     style: AchievementStatusStyles,
 }
 
 impl AchievementsView {
     pub fn new(app_state: Rc<AppState>, tx_event: UnboundedSender<Event>) -> Self {
+        let sort = app_state.sorting(VIEW_NAME);
         AchievementsView {
             app_state,
             tx_event,
@@ -61,8 +84,17 @@ impl AchievementsView {
             textbox_state: TextboxState::default(),
             achievements: BTreeMap::default(),
             account_achievements: HashMap::default(),
+            groups: Vec::default(),
+            categories: HashMap::default(),
+            rewards: Rewards::default(),
+            expanded_groups: HashSet::default(),
+            expanded_categories: HashSet::default(),
             visible_list_ids: Vec::default(),
+            visible_tree_nodes: Vec::default(),
+            invalid_query: None,
             searching: false,
+            browsing: false,
+            sort,
             style: AchievementStatusStyles {
                 normal: Style::default(),
                 done: Style::default().fg(Color::Green),
@@ -78,6 +110,7 @@ impl AchievementsView {
         account_achievement: Option<&AccountAchievement>,
         tracked: bool,
         styles: &AchievementStatusStyles,
+        indent: usize,
     ) -> ListItem<'a> {
         let mut style = if let Some(account_achievement) = account_achievement {
             if account_achievement.done {
@@ -95,11 +128,87 @@ impl AchievementsView {
             style = style.patch(styles.tracked);
         }
 
-        let text = Text::styled(achievement.name.clone(), style);
+        let text = Text::styled(format!("{}{}", " ".repeat(indent), achievement.name), style);
         ListItem::new(text)
     }
 
+    fn new_tree_item<'a>(&self, node: &TreeNode) -> Option<ListItem<'a>> {
+        match node {
+            TreeNode::Group(id) => self
+                .groups
+                .iter()
+                .find(|group| &group.id == id)
+                .map(|group| {
+                    ListItem::new(Spans::from(Span::styled(
+                        format!(
+                            "{} {}",
+                            Self::expand_symbol(self.expanded_groups.contains(id)),
+                            group.name
+                        ),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    )))
+                }),
+            TreeNode::Category(id) => self.categories.get(id).map(|category| {
+                let done = category
+                    .achievements
+                    .iter()
+                    .filter(|id| {
+                        self.account_achievements
+                            .get(id)
+                            .is_some_and(|account_achievement| account_achievement.done)
+                    })
+                    .count();
+                ListItem::new(format!(
+                    "  {} {} ({}/{})",
+                    Self::expand_symbol(self.expanded_categories.contains(id)),
+                    category.name,
+                    done,
+                    category.achievements.len()
+                ))
+            }),
+            TreeNode::Achievement(id) => self.achievements.get(id).map(|achievement| {
+                Self::new_list_item(
+                    achievement,
+                    self.account_achievements.get(id),
+                    self.app_state.is_tracked(&Track::Achievement(*id)),
+                    &self.style,
+                    6,
+                )
+            }),
+        }
+    }
+
+    fn expand_symbol(expanded: bool) -> &'static str {
+        if expanded {
+            "▾"
+        } else {
+            "▸"
+        }
+    }
+
+    /// The number of rows in the list that is currently being displayed
+    fn visible_len(&self) -> usize {
+        if self.browsing {
+            self.visible_tree_nodes.len()
+        } else {
+            self.visible_list_ids.len()
+        }
+    }
+
+    fn selected_node(&self) -> Option<&TreeNode> {
+        self.list_state
+            .selected()
+            .and_then(|selected_index| self.visible_tree_nodes.get(selected_index))
+    }
+
     fn selected_id(&self) -> Option<usize> {
+        if self.browsing {
+            return match self.selected_node() {
+                Some(TreeNode::Achievement(id)) => Some(*id),
+                _ => None,
+            };
+        }
+
         if let Some(selected_index) = self.list_state.selected() {
             self.visible_list_ids
                 .get(selected_index)
@@ -109,36 +218,188 @@ impl AchievementsView {
         }
     }
 
+    /// Expand or collapse the selected group or category in the browser
+    fn set_selected_expanded(&mut self, expanded: bool) {
+        match self.selected_node().cloned() {
+            Some(TreeNode::Group(id)) => {
+                if expanded {
+                    self.expanded_groups.insert(id);
+                } else {
+                    self.expanded_groups.remove(&id);
+                }
+            }
+            Some(TreeNode::Category(id)) => {
+                if expanded {
+                    self.expanded_categories.insert(id);
+                } else {
+                    self.expanded_categories.remove(&id);
+                }
+            }
+            _ => return,
+        }
+        self.update_tree();
+    }
+
+    fn toggle_selected_expanded(&mut self) {
+        let expanded = match self.selected_node() {
+            Some(TreeNode::Group(id)) => self.expanded_groups.contains(id),
+            Some(TreeNode::Category(id)) => self.expanded_categories.contains(id),
+            _ => return,
+        };
+        self.set_selected_expanded(!expanded);
+    }
+
+    fn sort_list(&mut self) {
+        let achievements = &self.achievements;
+        let account_achievements = &self.account_achievements;
+        let sort = self.sort;
+        self.visible_list_ids
+            .sort_by(|a, b| match (achievements.get(a), achievements.get(b)) {
+                (Some(a), Some(b)) => sort.compare(
+                    (a, account_achievements.get(&a.id)),
+                    (b, account_achievements.get(&b.id)),
+                ),
+                _ => a.cmp(b),
+            });
+    }
+
+    fn cycle_sort(&mut self) {
+        self.sort = self.sort.next();
+        self.app_state.set_sorting(VIEW_NAME, self.sort);
+        self.update_filter();
+        let _ = self.tx_event.send(Event::StatusMessage(format!(
+            "Sorting achievements by {}",
+            self.sort.name()
+        )));
+    }
+
+    /// Rebuild the rows of the browser from the expanded groups and categories.
+    ///
+    /// Only achievements that pass the current filter are shown. While a filter is active, groups
+    /// and categories without any matching achievements are hidden.
+    fn update_tree(&mut self) {
+        let filtering = !self.textbox_state.content().is_empty();
+        // Position of each visible achievement so categories follow the selected sort order
+        let visible_ids = self
+            .visible_list_ids
+            .iter()
+            .enumerate()
+            .map(|(position, id)| (*id, position))
+            .collect::<HashMap<usize, usize>>();
+
+        let mut nodes = vec![];
+        for group in &self.groups {
+            let mut categories = group
+                .categories
+                .iter()
+                .filter_map(|id| self.categories.get(id))
+                .map(|category| {
+                    let mut achievements = category
+                        .achievements
+                        .iter()
+                        .filter_map(|id| visible_ids.get(id).map(|position| (*position, *id)))
+                        .collect::<Vec<(usize, usize)>>();
+                    achievements.sort_unstable();
+                    let achievements = achievements
+                        .into_iter()
+                        .map(|(_, id)| TreeNode::Achievement(id))
+                        .collect::<Vec<TreeNode>>();
+                    (category, achievements)
+                })
+                .filter(|(_, achievements)| !filtering || !achievements.is_empty())
+                .collect::<Vec<(&AchievementCategory, Vec<TreeNode>)>>();
+
+            if filtering && categories.is_empty() {
+                continue;
+            }
+            categories.sort_by_key(|(category, _)| category.order);
+
+            nodes.push(TreeNode::Group(group.id.clone()));
+            if !self.expanded_groups.contains(&group.id) {
+                continue;
+            }
+
+            for (category, achievements) in categories {
+                nodes.push(TreeNode::Category(category.id));
+                if self.expanded_categories.contains(&category.id) {
+                    nodes.extend(achievements);
+                }
+            }
+        }
+        self.visible_tree_nodes = nodes;
+
+        if self.browsing {
+            self.list_state
+                .move_cursor(self.visible_tree_nodes.len(), CursorMovement::None);
+        }
+    }
+
+    /// Select an achievement in the list, clearing the search if it's filtered out
+    fn show_achievement(&mut self, id: usize) {
+        self.browsing = false;
+        self.searching = false;
+        if !self.visible_list_ids.contains(&id) {
+            self.textbox_state.clear();
+            self.update_filter();
+        }
+
+        if let Some(position) = self
+            .visible_list_ids
+            .iter()
+            .position(|visible| *visible == id)
+        {
+            self.list_state.select(None);
+            self.list_state
+                .move_cursor(self.visible_len(), CursorMovement::Down(position as u16));
+        }
+    }
+
     fn update_filter(&mut self) {
+        let content = self.textbox_state.content();
+        let query = match Query::parse(content) {
+            Ok(query) => query,
+            Err(err) => {
+                if self.invalid_query.as_ref() != Some(content) {
+                    self.invalid_query = Some(content.clone());
+                    let _ = self
+                        .tx_event
+                        .send(Event::StatusMessage(format!("Invalid search: {}", err)));
+                }
+                return;
+            }
+        };
+        self.invalid_query = None;
+
         self.visible_list_ids = self
             .achievements
             .iter()
             .filter_map(|(id, achievement)| {
-                if achievement
-                    .name
-                    .to_lowercase()
-                    .contains(&self.textbox_state.content().to_lowercase())
-                {
+                let subject = Subject {
+                    achievement,
+                    account_achievement: self.account_achievements.get(id),
+                    tracked: self.app_state.is_tracked(&Track::Achievement(*id)),
+                };
+                if query.matches(&subject) {
                     Some(*id)
                 } else {
                     None
                 }
             })
             .collect::<Vec<usize>>();
+        self.sort_list();
+        self.update_tree();
 
         // HACK Since the ListState offset can cause an 'index out of bounds' panic, we have to select nothing to reset the ListState and then re-apply cursor position
         let before_pos = self.list_state.selected().unwrap_or(0) as u16;
         self.list_state.select(None);
-        self.list_state.move_cursor(
-            self.visible_list_ids.len(),
-            CursorMovement::Down(before_pos),
-        );
+        self.list_state
+            .move_cursor(self.visible_len(), CursorMovement::Down(before_pos));
     }
 }
 
 impl View for AchievementsView {
     fn name(&self) -> &'static str {
-        "Achievements"
+        VIEW_NAME
     }
 
     fn draw(&mut self, frame: &mut Frame<CrosstermBackend<Stdout>>, area: Rect) {
@@ -166,21 +427,28 @@ impl View for AchievementsView {
             };
 
         // ACHIEVEMENT LIST
-        let list_items = self
-            .visible_list_ids
-            .iter()
-            .filter_map(|id| self.achievements.get(id))
-            .map(|achievement| {
-                let account_achievement = self.account_achievements.get(&achievement.id);
-                Self::new_list_item(
-                    achievement,
-                    account_achievement,
-                    self.app_state
-                        .is_tracked(&Track::Achievement(achievement.id)),
-                    &self.style,
-                )
-            })
-            .collect::<Vec<ListItem>>();
+        let list_items = if self.browsing {
+            self.visible_tree_nodes
+                .iter()
+                .filter_map(|node| self.new_tree_item(node))
+                .collect::<Vec<ListItem>>()
+        } else {
+            self.visible_list_ids
+                .iter()
+                .filter_map(|id| self.achievements.get(id))
+                .map(|achievement| {
+                    let account_achievement = self.account_achievements.get(&achievement.id);
+                    Self::new_list_item(
+                        achievement,
+                        account_achievement,
+                        self.app_state
+                            .is_tracked(&Track::Achievement(achievement.id)),
+                        &self.style,
+                        0,
+                    )
+                })
+                .collect::<Vec<ListItem>>()
+        };
 
         // RENDER
         // achievement list in the left sidebar
@@ -195,13 +463,12 @@ impl View for AchievementsView {
         // Render the main selected achievement information in the right panel
         if let Some(achievement) = self
             .selected_id()
-            .map(|achievement_id| self.achievements.get(&achievement_id))
-            .flatten()
+            .and_then(|achievement_id| self.achievements.get(&achievement_id))
         {
             let account_achievement = self.account_achievements.get(&achievement.id);
 
             frame.render_widget(
-                AchievementInfo::new_widget(achievement, account_achievement),
+                AchievementInfo::new_widget(achievement, account_achievement, &self.rewards),
                 main_panel,
             );
         }
@@ -246,27 +513,50 @@ impl View for AchievementsView {
             match event.input {
                 InputKind::MoveUp(amount) => {
                     self.list_state
-                        .move_cursor(self.visible_list_ids.len(), CursorMovement::Up(amount));
+                        .move_cursor(self.visible_len(), CursorMovement::Up(amount));
                     return true;
                 }
                 InputKind::MoveDown(amount) => {
                     self.list_state
-                        .move_cursor(self.visible_list_ids.len(), CursorMovement::Down(amount));
+                        .move_cursor(self.visible_len(), CursorMovement::Down(amount));
                     return true;
                 }
                 InputKind::Search => {
                     self.searching = !self.searching;
                     return true;
                 }
+                InputKind::Sort => {
+                    self.cycle_sort();
+                    return true;
+                }
+                InputKind::Browse => {
+                    self.browsing = !self.browsing;
+                    self.list_state.select(None);
+                    self.list_state
+                        .move_cursor(self.visible_len(), CursorMovement::None);
+                    return true;
+                }
+                InputKind::Confirm if self.browsing => {
+                    self.toggle_selected_expanded();
+                    return true;
+                }
+                InputKind::MoveRight(_) if self.browsing => {
+                    self.set_selected_expanded(true);
+                    return true;
+                }
+                InputKind::MoveLeft(_) if self.browsing => {
+                    self.set_selected_expanded(false);
+                    return true;
+                }
                 InputKind::Top => {
-                    if self.visible_list_ids.is_empty() {
+                    if self.visible_len() > 0 {
                         self.list_state.select(Some(0));
                     }
                     return true;
                 }
                 InputKind::Bottom => {
                     self.list_state
-                        .select(Some(self.visible_list_ids.len() - 1));
+                        .move_cursor(self.visible_len(), CursorMovement::Down(u16::MAX));
                     return true;
                 }
                 InputKind::Back => {
@@ -275,12 +565,13 @@ impl View for AchievementsView {
                     return true;
                 }
                 InputKind::Select => {
-                    self.selected_id().map(|id| {
-                        self.achievements.get(&id).map(|achievement| {
-                            self.tx_event
-                                .send(Event::ToggleTrack(Track::Achievement(achievement.id)))
-                        })
-                    });
+                    if let Some(achievement) =
+                        self.selected_id().and_then(|id| self.achievements.get(&id))
+                    {
+                        let _ = self
+                            .tx_event
+                            .send(Event::ToggleTrack(Track::Achievement(achievement.id)));
+                    }
                     return true;
                 }
                 _ => {}
@@ -293,7 +584,7 @@ impl View for AchievementsView {
         match event {
             Event::AchievementsLoaded(all_achievements) => {
                 self.achievements = all_achievements
-                    .into_iter()
+                    .iter()
                     .map(|achievement| (achievement.id, achievement.to_owned()))
                     .collect::<BTreeMap<usize, Achievement>>();
                 self.update_filter()
@@ -306,7 +597,25 @@ impl View for AchievementsView {
                         (account_achievement.id, account_achievement.to_owned())
                     })
                     .collect();
+                // Progress affects both the sort order and queries like `done:yes`
+                self.update_filter();
+            }
+            Event::AchievementGroupsLoaded(groups) => {
+                self.groups = groups.iter().cloned().collect();
+                self.groups.sort_by_key(|group| group.order);
+                self.update_tree();
+            }
+            Event::AchievementCategoriesLoaded(categories) => {
+                self.categories = categories
+                    .iter()
+                    .map(|category| (category.id, category.to_owned()))
+                    .collect();
+                self.update_tree();
             }
+            // Queries like `tracked:yes` depend on what's tracked
+            Event::ToggleTrack(_) => self.update_filter(),
+            Event::RewardsLoaded(rewards) => self.rewards = rewards.to_owned(),
+            Event::ShowAchievement(id) => self.show_achievement(*id),
             _ => {}
         };
     }
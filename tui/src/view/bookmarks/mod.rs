@@ -1,33 +1,54 @@
 mod popup;
 
-use std::{io::Stdout, rc::Rc};
+use std::{collections::HashSet, io::Stdout, rc::Rc};
 
 use copypasta::{ClipboardContext, ClipboardProvider};
 use log::debug;
 use tokio::sync::mpsc::UnboundedSender;
 use tui::{
     backend::CrosstermBackend,
-    layout::Rect,
-    widgets::{List, ListItem, ListState},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, List, ListItem, ListState},
     Frame,
 };
 
 use crate::{
     input::{InputEvent, InputKind},
-    widget::list_selection::{CursorMovement, ListSelection},
+    widget::{
+        list_selection::{CursorMovement, ListSelection},
+        text_box::{Textbox, TextboxState},
+    },
+};
+use orrient::{
+    bookmarks::{Bookmark, BookmarkKind},
+    events::Event,
+    state::AppState,
 };
-use orrient::{bookmarks::Bookmark, events::Event, state::AppState};
 
 use self::popup::CustomBookmarkPopupState;
 
 use super::View;
 
+/// A row in the bookmark list
+#[derive(Clone, Debug, PartialEq)]
+enum BookmarkNode {
+    Folder(String),
+    /// The index of the bookmark
+    Bookmark(usize),
+}
+
 pub struct BookmarksView {
     app_state: Rc<AppState>,
     tx_event: UnboundedSender<Event>,
     clipboard_ctx: Option<ClipboardContext>,
     bookmarks: Vec<Bookmark>,
+    visible_nodes: Vec<BookmarkNode>,
+    collapsed_folders: HashSet<String>,
     list_state: ListState,
+    textbox_state: TextboxState,
+    searching: bool,
     add_popup: CustomBookmarkPopupState,
 }
 
@@ -40,18 +61,186 @@ impl BookmarksView {
                 None
             }
         };
-        let bookmarks = app_state.bookmarks().into_iter().collect::<Vec<Bookmark>>();
-        let mut list_state = ListState::default();
-        list_state.move_cursor(bookmarks.len(), CursorMovement::None);
+        let bookmarks = app_state.bookmarks();
         let add_popup = CustomBookmarkPopupState::new(tx_event.clone());
 
-        Self {
+        let mut view = Self {
             app_state,
             tx_event,
             clipboard_ctx,
             bookmarks,
-            list_state,
+            visible_nodes: Vec::default(),
+            collapsed_folders: HashSet::default(),
+            list_state: ListState::default(),
+            textbox_state: TextboxState::default(),
+            searching: false,
             add_popup,
+        };
+        view.update_nodes();
+        view
+    }
+
+    /// Rebuild the visible rows from the bookmarks, in the order they were arranged. A folder
+    /// is shown where its first bookmark is. Folders are expanded while searching.
+    fn update_nodes(&mut self) {
+        let search = self.textbox_state.content();
+        let mut folders = HashSet::new();
+        let mut nodes = vec![];
+        for bookmark in self.bookmarks.iter() {
+            let folder = match &bookmark.folder {
+                Some(folder) => folder,
+                None => continue,
+            };
+            if !folders.insert(folder) {
+                continue;
+            }
+            let children = self
+                .bookmarks
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| other.folder.as_ref() == Some(folder))
+                .filter(|(_, other)| other.matches(search))
+                .map(|(index, _)| BookmarkNode::Bookmark(index))
+                .collect::<Vec<BookmarkNode>>();
+            if children.is_empty() {
+                continue;
+            }
+            nodes.push(BookmarkNode::Folder(folder.clone()));
+            if !search.is_empty() || !self.collapsed_folders.contains(folder) {
+                nodes.extend(children);
+            }
+        }
+        // Bookmarks outside of a folder come after the folders
+        nodes.extend(
+            self.bookmarks
+                .iter()
+                .enumerate()
+                .filter(|(_, bookmark)| bookmark.folder.is_none() && bookmark.matches(search))
+                .map(|(index, _)| BookmarkNode::Bookmark(index)),
+        );
+        self.visible_nodes = nodes;
+        self.list_state
+            .move_cursor(self.visible_nodes.len(), CursorMovement::None);
+    }
+
+    fn selected_node(&self) -> Option<&BookmarkNode> {
+        self.list_state
+            .selected()
+            .and_then(|selected_index| self.visible_nodes.get(selected_index))
+    }
+
+    fn selected_bookmark(&self) -> Option<&Bookmark> {
+        match self.selected_node() {
+            Some(BookmarkNode::Bookmark(index)) => self.bookmarks.get(*index),
+            _ => None,
+        }
+    }
+
+    /// Expand or collapse the selected folder
+    fn set_selected_expanded(&mut self, expanded: bool) {
+        match self.selected_node().cloned() {
+            Some(BookmarkNode::Folder(folder)) => {
+                if expanded {
+                    self.collapsed_folders.remove(&folder);
+                } else {
+                    self.collapsed_folders.insert(folder);
+                }
+            }
+            _ => return,
+        }
+        self.update_nodes();
+    }
+
+    fn toggle_selected_expanded(&mut self) {
+        let expanded = match self.selected_node() {
+            Some(BookmarkNode::Folder(folder)) => !self.collapsed_folders.contains(folder),
+            _ => return,
+        };
+        self.set_selected_expanded(!expanded);
+    }
+
+    /// Move the selected bookmark up or down within its folder, keeping it selected
+    fn shift_selected(&mut self, up: bool) {
+        let bookmark = match self.selected_bookmark() {
+            Some(bookmark) => bookmark.clone(),
+            None => return,
+        };
+        self.app_state.shift_bookmark(&bookmark, up);
+        self.bookmarks = self.app_state.bookmarks();
+        self.update_nodes();
+        let bookmarks = &self.bookmarks;
+        if let Some(selected_index) = self.visible_nodes.iter().position(|node| match node {
+            BookmarkNode::Bookmark(index) => bookmarks.get(*index) == Some(&bookmark),
+            _ => false,
+        }) {
+            self.list_state.select(Some(selected_index));
+        }
+    }
+}
+
+impl BookmarksView {
+    fn new_tree_item<'a>(&self, node: &BookmarkNode) -> Option<ListItem<'a>> {
+        match node {
+            BookmarkNode::Folder(folder) => {
+                let expanded = !self.textbox_state.content().is_empty()
+                    || !self.collapsed_folders.contains(folder);
+                Some(ListItem::new(Spans::from(Span::styled(
+                    format!("{} {}", if expanded { "▾" } else { "▸" }, folder),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ))))
+            }
+            BookmarkNode::Bookmark(index) => self.bookmarks.get(*index).map(Self::new_list_item),
+        }
+    }
+
+    fn new_list_item<'a>(bookmark: &Bookmark) -> ListItem<'a> {
+        let color = match bookmark.kind {
+            BookmarkKind::Waypoint => Color::LightBlue,
+            BookmarkKind::Item(_) => Color::Yellow,
+            BookmarkKind::Recipe(_) => Color::LightYellow,
+            BookmarkKind::Achievement(_) => Color::Green,
+            BookmarkKind::BuildTemplate => Color::Magenta,
+            BookmarkKind::Url => Color::Cyan,
+        };
+        let indent = if bookmark.folder.is_some() { "  " } else { "" };
+        let tags = bookmark
+            .tags
+            .iter()
+            .map(|tag| format!(" #{}", tag))
+            .collect::<String>();
+        ListItem::new(Spans::from(vec![
+            Span::raw(indent),
+            Span::styled(
+                format!("{:<12}", bookmark.kind.name()),
+                Style::default().fg(color),
+            ),
+            Span::raw(bookmark.name.clone()),
+            Span::styled(tags, Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("  {}", bookmark.link),
+                Style::default().add_modifier(Modifier::DIM),
+            ),
+        ]))
+    }
+
+    // Items and achievements are opened in their tabs, everything else is copied to the clipboard
+    fn open(&mut self, bookmark: Bookmark) {
+        match bookmark.kind {
+            BookmarkKind::Item(id) => {
+                let _ = self.tx_event.send(Event::ShowItem(id));
+            }
+            BookmarkKind::Achievement(id) => {
+                let _ = self.tx_event.send(Event::ShowAchievement(id));
+            }
+            _ => {
+                if let Some(clipboard) = self.clipboard_ctx.as_mut() {
+                    let _ = clipboard.set_contents(bookmark.link.clone());
+                    let _ = self.tx_event.send(Event::StatusMessage(format!(
+                        "Copied content of '{}' to clipboard.",
+                        bookmark.name
+                    )));
+                }
+            }
         }
     }
 }
@@ -62,14 +251,39 @@ impl View for BookmarksView {
     }
 
     fn draw(&mut self, frame: &mut Frame<CrosstermBackend<Stdout>>, area: Rect) {
+        let (list_panel, search_panel) =
+            if !self.searching && self.textbox_state.content().is_empty() {
+                (area, None)
+            } else {
+                let layout = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(2), Constraint::Percentage(100)])
+                    .split(area);
+                (layout[1], Some(layout[0]))
+            };
+
         let list = List::new(
-            self.bookmarks
+            self.visible_nodes
                 .iter()
-                .map(|bookmark| ListItem::new(bookmark.name.as_str()))
+                .filter_map(|node| self.new_tree_item(node))
                 .collect::<Vec<ListItem>>(),
         )
         .highlight_symbol(">>");
-        frame.render_stateful_widget(list, area, &mut self.list_state);
+        frame.render_stateful_widget(list, list_panel, &mut self.list_state);
+
+        if let Some(search_panel) = search_panel {
+            frame.render_stateful_widget(
+                Textbox::new()
+                    .block(Block::default().borders(Borders::BOTTOM))
+                    .style(if self.searching {
+                        Style::default()
+                    } else {
+                        Style::default().add_modifier(Modifier::DIM)
+                    }),
+                search_panel,
+                &mut self.textbox_state,
+            );
+        }
 
         self.add_popup.draw(frame, area);
     }
@@ -79,41 +293,87 @@ impl View for BookmarksView {
             return true;
         }
 
+        if self.searching {
+            match event.input {
+                InputKind::Confirm => {
+                    self.searching = false;
+                    return true;
+                }
+                InputKind::Back => {
+                    self.textbox_state.clear();
+                    self.searching = false;
+                    self.update_nodes();
+                    return true;
+                }
+                _ => {}
+            }
+
+            if self.textbox_state.handle_input(event) {
+                self.update_nodes();
+                return true;
+            }
+            return false;
+        }
+
         match event.input {
             InputKind::MoveUp(amount) => {
                 self.list_state
-                    .move_cursor(self.bookmarks.len(), CursorMovement::Up(amount));
+                    .move_cursor(self.visible_nodes.len(), CursorMovement::Up(amount));
                 true
             }
             InputKind::MoveDown(amount) => {
                 self.list_state
-                    .move_cursor(self.bookmarks.len(), CursorMovement::Down(amount));
+                    .move_cursor(self.visible_nodes.len(), CursorMovement::Down(amount));
+                true
+            }
+            InputKind::ShiftUp => {
+                self.shift_selected(true);
+                true
+            }
+            InputKind::ShiftDown => {
+                self.shift_selected(false);
+                true
+            }
+            InputKind::MoveRight(_) => {
+                self.set_selected_expanded(true);
+                true
+            }
+            InputKind::MoveLeft(_) => {
+                self.set_selected_expanded(false);
+                true
+            }
+            InputKind::Search => {
+                self.searching = true;
+                true
+            }
+            InputKind::Back if !self.textbox_state.content().is_empty() => {
+                self.textbox_state.clear();
+                self.update_nodes();
                 true
             }
             InputKind::New => {
                 self.add_popup.active(true);
                 true
             }
+            InputKind::Edit => {
+                if let Some(bookmark) = self.selected_bookmark().cloned() {
+                    self.add_popup.edit(bookmark);
+                }
+                true
+            }
             InputKind::Delete => {
-                if let Some(selected) = self.list_state.selected() {
-                    self.app_state
-                        .remove_bookmark(self.bookmarks.remove(selected));
-                    self.list_state
-                        .move_cursor(self.bookmarks.len(), CursorMovement::None);
+                if let Some(bookmark) = self.selected_bookmark().cloned() {
+                    self.app_state.remove_bookmark(bookmark);
+                    self.bookmarks = self.app_state.bookmarks();
+                    self.update_nodes();
                 }
                 true
             }
             InputKind::Confirm => {
-                if let Some(clipboard) = self.clipboard_ctx.as_mut() {
-                    if let Some(selected) = self.list_state.selected() {
-                        if let Some(bookmark) = self.bookmarks.get(selected) {
-                            let _ = clipboard.set_contents(bookmark.link.clone());
-                            let _ = self.tx_event.send(Event::StatusMessage(format!(
-                                "Copied content of '{}' to clipboard.",
-                                bookmark.name
-                            )));
-                        }
-                    }
+                if let Some(bookmark) = self.selected_bookmark().cloned() {
+                    self.open(bookmark);
+                } else {
+                    self.toggle_selected_expanded();
                 }
                 true
             }
@@ -124,8 +384,24 @@ impl View for BookmarksView {
     fn handle_event(&mut self, event: &Event) {
         match event {
             Event::AddBookmark(bookmark) => {
-                self.bookmarks.push(bookmark.clone());
                 self.app_state.add_bookmark(bookmark.clone());
+                self.bookmarks = self.app_state.bookmarks();
+                self.update_nodes();
+            }
+            Event::ReplaceBookmark(bookmark, new_bookmark) => {
+                if !self
+                    .app_state
+                    .replace_bookmark(&bookmark.name, new_bookmark.clone())
+                {
+                    let _ = self.tx_event.send(Event::StatusMessage(format!(
+                        "A bookmark named '{}' already exists.",
+                        new_bookmark.name
+                    )));
+                    self.add_popup
+                        .reopen(bookmark.clone(), new_bookmark.clone());
+                }
+                self.bookmarks = self.app_state.bookmarks();
+                self.update_nodes();
             }
             _ => {}
         }
@@ -3,8 +3,8 @@ use std::{cell::Cell, iter};
 use tokio::sync::mpsc::UnboundedSender;
 use tui::{
     layout::{Constraint, Direction, Layout, Margin, Rect},
-    style::{Modifier, Style},
-    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
     Frame,
 };
 
@@ -20,10 +20,16 @@ use orrient::{
     events::Event,
 };
 
+const FIELDS: [&str; 4] = ["Name", "Link", "Folder", "Tags"];
+
 pub struct CustomBookmarkPopupState {
     name_textbox_state: TextboxState,
     link_textbox_state: TextboxState,
+    folder_textbox_state: TextboxState,
+    tags_textbox_state: TextboxState,
     list_state: ListState,
+    /// The bookmark being edited, if not adding a new one
+    editing: Option<Bookmark>,
     active: Cell<bool>,
     tx_event: UnboundedSender<Event>,
 }
@@ -33,7 +39,10 @@ impl CustomBookmarkPopupState {
         let mut popup = Self {
             name_textbox_state: TextboxState::default(),
             link_textbox_state: TextboxState::default(),
+            folder_textbox_state: TextboxState::default(),
+            tags_textbox_state: TextboxState::default(),
             list_state: ListState::default(),
+            editing: None,
             active: Cell::new(false),
             tx_event,
         };
@@ -48,15 +57,57 @@ impl CustomBookmarkPopupState {
     fn reset(&mut self) {
         self.name_textbox_state = TextboxState::default();
         self.link_textbox_state = TextboxState::default();
+        self.folder_textbox_state = TextboxState::default();
+        self.tags_textbox_state = TextboxState::default();
         self.list_state = ListState::default();
         self.list_state.select(Some(0));
+        self.editing = None;
+    }
+
+    /// Open the popup filled in with an existing bookmark
+    pub fn edit(&mut self, bookmark: Bookmark) {
+        self.reopen(bookmark.clone(), bookmark);
+    }
+
+    /// Open the popup to keep editing `bookmark`, filled in with `edited` values that couldn't be
+    /// saved
+    pub fn reopen(&mut self, bookmark: Bookmark, edited: Bookmark) {
+        self.reset();
+        self.name_textbox_state.set_content(edited.name);
+        self.link_textbox_state.set_content(edited.link);
+        self.folder_textbox_state
+            .set_content(edited.folder.unwrap_or_default());
+        self.tags_textbox_state.set_content(edited.tags.join(", "));
+        self.editing = Some(bookmark);
+        self.active(true);
+    }
+
+    fn selected_textbox(&mut self) -> Option<&mut TextboxState> {
+        match self.list_state.selected() {
+            Some(0) => Some(&mut self.name_textbox_state),
+            Some(1) => Some(&mut self.link_textbox_state),
+            Some(2) => Some(&mut self.folder_textbox_state),
+            Some(3) => Some(&mut self.tags_textbox_state),
+            _ => None,
+        }
     }
 
-    fn finish(&mut self) -> Bookmark {
+    fn finish(&mut self, kind: BookmarkKind) -> Bookmark {
+        let folder = self.folder_textbox_state.take().trim().to_string();
+        // Tags are entered as a comma separated list
+        let tags = self
+            .tags_textbox_state
+            .take()
+            .split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
         let bookmark = Bookmark {
-            kind: BookmarkKind::Waypoint,
+            kind,
             name: self.name_textbox_state.take(),
-            link: self.link_textbox_state.take(),
+            link: self.link_textbox_state.take().trim().to_string(),
+            folder: Some(folder).filter(|folder| !folder.is_empty()),
+            tags,
         };
         self.reset();
         bookmark
@@ -67,7 +118,7 @@ impl CustomBookmarkPopupState {
             return;
         }
 
-        let (width, height) = (50, 10);
+        let (width, height) = (60, 10);
         if area.width < width || area.height < height {
             return;
         }
@@ -79,7 +130,11 @@ impl CustomBookmarkPopupState {
         let area = Rect::new(x, y, width, height);
         let background = Block::default()
             .borders(Borders::ALL)
-            .title("Add bookmark")
+            .title(if self.editing.is_some() {
+                "Edit bookmark"
+            } else {
+                "Add bookmark"
+            })
             .style(style);
         frame.render_widget(Clear, area);
         frame.render_widget(background, area);
@@ -91,44 +146,77 @@ impl CustomBookmarkPopupState {
 
         let h_chunks = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Length(2), Constraint::Length(area.width - 2)])
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(8),
+                Constraint::Length(area.width - 10),
+            ])
             .split(area);
 
         let v_chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(1), Constraint::Length(1)])
-            .split(h_chunks[1]);
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .split(h_chunks[2]);
 
         let list = List::new(
             iter::repeat(ListItem::new(" "))
-                .take(2)
+                .take(FIELDS.len())
                 .collect::<Vec<ListItem>>(),
         )
         .highlight_symbol(">")
         .style(style);
 
-        let name_input_box =
-            Textbox::new().style(style.patch(Style::default().remove_modifier(Modifier::REVERSED)));
+        let labels = List::new(
+            FIELDS
+                .iter()
+                .map(|field| ListItem::new(*field))
+                .collect::<Vec<ListItem>>(),
+        )
+        .style(style);
 
-        let link_input_box =
-            Textbox::new().style(style.patch(Style::default().remove_modifier(Modifier::REVERSED)));
+        let input_box = || {
+            Textbox::new().style(style.patch(Style::default().remove_modifier(Modifier::REVERSED)))
+        };
 
         frame.render_stateful_widget(list, h_chunks[0], &mut self.list_state);
-        frame.render_stateful_widget(name_input_box, v_chunks[0], &mut self.name_textbox_state);
-        frame.render_stateful_widget(link_input_box, v_chunks[1], &mut self.link_textbox_state);
+        frame.render_widget(labels, h_chunks[1]);
+        frame.render_stateful_widget(input_box(), v_chunks[0], &mut self.name_textbox_state);
+        frame.render_stateful_widget(input_box(), v_chunks[1], &mut self.link_textbox_state);
+        frame.render_stateful_widget(input_box(), v_chunks[2], &mut self.folder_textbox_state);
+        frame.render_stateful_widget(input_box(), v_chunks[3], &mut self.tags_textbox_state);
 
-        match self.list_state.selected() {
-            Some(0) => {
-                let x = v_chunks[0].x + self.name_textbox_state.cursor_position();
-                let y = v_chunks[0].y;
-                frame.set_cursor(x, y);
+        // Show what kind of bookmark the link is, or why it can't be bookmarked
+        let link = self.link_textbox_state.content();
+        let (hint, hint_style) = if link.is_empty() {
+            (
+                "Chat code, URL or achievement ID".to_string(),
+                style.add_modifier(Modifier::DIM),
+            )
+        } else {
+            match BookmarkKind::from_link(link) {
+                Ok((kind, Some(chat_link))) => (
+                    format!("{}: {}", kind.name(), chat_link.describe()),
+                    style.fg(Color::Green),
+                ),
+                Ok((kind, None)) => (kind.name().to_string(), style.fg(Color::Green)),
+                Err(err) => (err, style.fg(Color::Red)),
             }
-            Some(1) => {
-                let x = v_chunks[1].x + self.link_textbox_state.cursor_position();
-                let y = v_chunks[1].y;
+        };
+        frame.render_widget(Paragraph::new(hint).style(hint_style), v_chunks[5]);
+
+        if let Some(selected) = self.list_state.selected() {
+            if let Some(textbox_state) = self.selected_textbox() {
+                let x = v_chunks[selected].x + textbox_state.cursor_position();
+                let y = v_chunks[selected].y;
                 frame.set_cursor(x, y);
             }
-            _ => {}
         }
     }
 
@@ -137,31 +225,47 @@ impl CustomBookmarkPopupState {
             return false;
         }
 
-        if match self.list_state.selected() {
-            Some(0) => self.name_textbox_state.handle_input(event),
-            Some(1) => self.link_textbox_state.handle_input(event),
-            _ => false,
-        } {
+        if self
+            .selected_textbox()
+            .is_some_and(|textbox_state| textbox_state.handle_input(event))
+        {
             return true;
         }
 
         match event.input {
             InputKind::Back => {
                 self.active(false);
+                if self.editing.is_some() {
+                    self.reset();
+                }
                 true
             }
             InputKind::Confirm => {
+                // Keep the popup open until the link is valid
+                let kind = match BookmarkKind::from_link(self.link_textbox_state.content()) {
+                    Ok((kind, _)) => kind,
+                    Err(_) => {
+                        self.list_state.select(Some(1));
+                        return true;
+                    }
+                };
                 self.active(false);
-                let bookmark = self.finish();
-                let _ = self.tx_event.send(Event::AddBookmark(bookmark));
+                let editing = self.editing.take();
+                let bookmark = self.finish(kind);
+                let _ = self.tx_event.send(match editing {
+                    Some(editing) => Event::ReplaceBookmark(editing, bookmark),
+                    None => Event::AddBookmark(bookmark),
+                });
                 true
             }
             InputKind::MoveUp(amount) => {
-                self.list_state.move_cursor(2, CursorMovement::Up(amount));
+                self.list_state
+                    .move_cursor(FIELDS.len(), CursorMovement::Up(amount));
                 true
             }
             InputKind::MoveDown(amount) => {
-                self.list_state.move_cursor(2, CursorMovement::Down(amount));
+                self.list_state
+                    .move_cursor(FIELDS.len(), CursorMovement::Down(amount));
                 true
             }
             _ => false,
@@ -1,53 +1,155 @@
-use std::{collections::HashMap, io::Stdout};
+use std::{collections::HashMap, io::Stdout, rc::Rc};
 
+use chrono::{DateTime, Utc};
 use tui::{
     backend::CrosstermBackend,
-    layout::Rect,
-    style::{Modifier, Style},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
     text::{Span, Spans},
     widgets::Paragraph,
     Frame,
 };
 
-use crate::input::InputEvent;
+use crate::input::{InputEvent, InputKind};
 use orrient::{
-    api::{Achievement, Dailies, Daily},
-    events::Event,
+    api::{Account, AccountAchievement, Achievement, Character, Dailies, Daily},
+    events::{DailyChecklist, Event},
+    reset::{self, Reset},
+    state::AppState,
 };
 
 use super::View;
 
 pub struct DailiesView {
+    app_state: Rc<AppState>,
     achievements: HashMap<usize, Achievement>,
+    account_achievements: HashMap<usize, AccountAchievement>,
     dailies: Option<Dailies>,
+    tomorrow_dailies: Option<Dailies>,
+    showing_tomorrow: bool,
+    account: Option<Account>,
+    characters: Vec<Character>,
+    show_unavailable: bool,
+    selected: usize,
+    checklist: DailyChecklist,
+    checklist_done: Option<(DailyChecklist, DateTime<Utc>)>,
     header_style: Style,
 }
 
 impl DailiesView {
-    pub fn new() -> Self {
+    pub fn new(app_state: Rc<AppState>) -> Self {
         DailiesView {
+            app_state,
             achievements: HashMap::default(),
+            account_achievements: HashMap::default(),
             dailies: None,
+            tomorrow_dailies: None,
+            showing_tomorrow: false,
+            account: None,
+            characters: Vec::default(),
+            show_unavailable: false,
+            selected: 0,
+            checklist: DailyChecklist::default(),
+            checklist_done: None,
             header_style: Style::default().add_modifier(Modifier::BOLD),
         }
     }
 
-    fn render_category(&self, title: String, dailies: &[Daily]) -> Vec<Spans> {
-        if !dailies.is_empty() {
+    fn shown_dailies(&self) -> Option<&Dailies> {
+        if self.showing_tomorrow {
+            self.tomorrow_dailies.as_ref()
+        } else {
+            self.dailies.as_ref()
+        }
+    }
+
+    // The time left until the next daily reset at 00:00 UTC
+    fn render_header(&self) -> Spans<'_> {
+        let countdown = reset::format_countdown(Reset::Daily.until_next());
+        let (title, countdown) = if self.showing_tomorrow {
+            ("Tomorrow", format!("available in {}", countdown))
+        } else {
+            ("Today", format!("resets in {}", countdown))
+        };
+        Spans::from(vec![
+            Span::styled(title, self.header_style),
+            Span::styled(
+                format!(" ({})", countdown),
+                Style::default().add_modifier(Modifier::DIM),
+            ),
+        ])
+    }
+
+    // The IDs of the dailies in the order they're displayed
+    fn daily_ids(&self) -> Vec<usize> {
+        self.shown_dailies().map_or(vec![], |dailies| {
+            dailies
+                .pve
+                .iter()
+                .chain(dailies.pvp.iter())
+                .chain(dailies.wvw.iter())
+                .chain(dailies.fractals.iter())
+                .chain(dailies.special.iter())
+                .filter(|daily| self.is_visible(daily))
+                .map(|daily| daily.id)
+                .collect()
+        })
+    }
+
+    // Dailies the account can't do are only shown when toggled on
+    fn is_visible(&self, daily: &Daily) -> bool {
+        self.achievements.contains_key(&daily.id)
+            && (self.show_unavailable || self.is_available(daily))
+    }
+
+    fn is_available(&self, daily: &Daily) -> bool {
+        daily.is_available(self.account.as_ref(), &self.characters)
+    }
+
+    fn selected_id(&self) -> Option<usize> {
+        self.daily_ids().get(self.selected).copied()
+    }
+
+    // A daily is done when the account has completed the achievement, or when it has been marked
+    // as done by hand since the last reset
+    fn is_done(&self, id: usize) -> bool {
+        self.account_achievements
+            .get(&id)
+            .is_some_and(|account_achievement| account_achievement.done)
+            || self.app_state.is_daily_done(id)
+    }
+
+    fn render_category(&self, title: String, dailies: &[Daily]) -> Vec<Spans<'_>> {
+        let selected_id = self.selected_id();
+        let rendered = dailies
+            .iter()
+            .filter(|daily| self.is_visible(daily))
+            .filter_map(|daily| self.render_daily(daily, selected_id == Some(daily.id)))
+            .collect::<Vec<Spans>>();
+        if !rendered.is_empty() {
             let mut group = vec![Spans::from(Span::styled(title, self.header_style))];
-            for a in dailies.iter().filter_map(|daily| self.render_daily(daily)) {
-                group.push(a)
-            }
+            group.extend(rendered);
             group
         } else {
             Vec::default()
         }
     }
 
-    fn render_daily(&self, daily: &Daily) -> Option<Spans> {
+    fn render_daily(&self, daily: &Daily, selected: bool) -> Option<Spans<'_>> {
         self.achievements.get(&daily.id).map(|achievement| {
+            let (checkbox, mut style) = if self.is_done(daily.id) {
+                ("[x] ", Style::default().fg(Color::Green))
+            } else {
+                ("[ ] ", Style::default())
+            };
+            if !self.is_available(daily) {
+                style = style.add_modifier(Modifier::DIM);
+            }
+            if selected {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
             Spans::from(vec![
-                Span::raw(format!("{}: ", achievement.name.clone())),
+                Span::styled(format!("{}{}: ", checkbox, achievement.name), style),
                 Span::styled(
                     achievement.requirement.clone(),
                     Style::default().add_modifier(Modifier::DIM),
@@ -55,6 +157,55 @@ impl DailiesView {
             ])
         })
     }
+
+    // What has been done today. Progress loaded before the last daily reset at 00:00 UTC is
+    // discarded until it's fetched again.
+    fn done_today(&self) -> Option<&DailyChecklist> {
+        self.checklist_done
+            .as_ref()
+            .filter(|(_, loaded_at)| !Reset::Daily.since(*loaded_at))
+            .map(|(done, _)| done)
+    }
+
+    fn render_checklist_category(
+        &self,
+        title: String,
+        ids: &[String],
+        done: Option<&Vec<String>>,
+    ) -> Vec<Spans<'_>> {
+        if ids.is_empty() {
+            return Vec::default();
+        }
+
+        let mut group = vec![Spans::from(Span::styled(title, self.header_style))];
+        for id in ids {
+            let is_done = done.is_some_and(|done| done.contains(id));
+            group.push(Spans::from(if is_done {
+                Span::styled(
+                    format!("[x] {}", Self::checklist_name(id)),
+                    Style::default().fg(Color::Green),
+                )
+            } else {
+                Span::raw(format!("[ ] {}", Self::checklist_name(id)))
+            }));
+        }
+        group
+    }
+
+    // The API only provides IDs like "admiral_taidha_covington", so turn them into something
+    // more readable
+    fn checklist_name(id: &str) -> String {
+        id.split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
 }
 
 impl View for DailiesView {
@@ -63,8 +214,14 @@ impl View for DailiesView {
     }
 
     fn draw(&mut self, frame: &mut Frame<CrosstermBackend<Stdout>>, area: Rect) {
-        if let Some(dailies) = &self.dailies {
-            let blank = vec![Spans::default()];
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(area);
+
+        let header = vec![self.render_header(), Spans::default()];
+        if let Some(dailies) = self.shown_dailies() {
+            let blank = [Spans::default()];
             let pve = self.render_category("PvE".to_string(), &dailies.pve);
             let pvp = self.render_category("PvP".to_string(), &dailies.pvp);
             let wvw = self.render_category("WvW".to_string(), &dailies.wvw);
@@ -72,36 +229,129 @@ impl View for DailiesView {
             let special = self.render_category("Special".to_string(), &dailies.special);
 
             let widget = Paragraph::new(
-                pve.into_iter()
+                header
+                    .into_iter()
+                    .chain(pve)
                     .chain(blank.iter().map(ToOwned::to_owned))
-                    .chain(pvp.into_iter())
+                    .chain(pvp)
                     .chain(blank.iter().map(ToOwned::to_owned))
-                    .chain(wvw.into_iter())
+                    .chain(wvw)
                     .chain(blank.iter().map(ToOwned::to_owned))
-                    .chain(fractals.into_iter())
+                    .chain(fractals)
                     .chain(blank.iter().map(ToOwned::to_owned))
-                    .chain(special.into_iter())
+                    .chain(special)
                     .collect::<Vec<Spans>>(),
             );
-            frame.render_widget(widget, area);
+            frame.render_widget(widget, layout[0]);
+        } else {
+            frame.render_widget(Paragraph::new(header), layout[0]);
         }
+
+        let done = self.done_today();
+        let blank = [Spans::default()];
+        let crafting = self.render_checklist_category(
+            "Crafting".to_string(),
+            &self.checklist.crafting,
+            done.map(|done| &done.crafting),
+        );
+        let map_chests = self.render_checklist_category(
+            "Map Chests".to_string(),
+            &self.checklist.map_chests,
+            done.map(|done| &done.map_chests),
+        );
+        let world_bosses = self.render_checklist_category(
+            "World Bosses".to_string(),
+            &self.checklist.world_bosses,
+            done.map(|done| &done.world_bosses),
+        );
+
+        let widget = Paragraph::new(
+            crafting
+                .into_iter()
+                .chain(blank.iter().map(ToOwned::to_owned))
+                .chain(map_chests)
+                .chain(blank.iter().map(ToOwned::to_owned))
+                .chain(world_bosses)
+                .collect::<Vec<Spans>>(),
+        );
+        frame.render_widget(widget, layout[1]);
     }
 
-    fn handle_input(&mut self, _: &InputEvent) -> bool {
-        false
+    fn handle_input(&mut self, event: &InputEvent) -> bool {
+        let len = self.daily_ids().len();
+        match event.input {
+            InputKind::MoveUp(amount) => {
+                self.selected = self.selected.saturating_sub(amount as usize);
+                true
+            }
+            InputKind::MoveDown(amount) => {
+                self.selected = (self.selected + amount as usize).min(len.saturating_sub(1));
+                true
+            }
+            InputKind::Top => {
+                self.selected = 0;
+                true
+            }
+            InputKind::Bottom => {
+                self.selected = len.saturating_sub(1);
+                true
+            }
+            InputKind::MoveLeft(_) | InputKind::MoveRight(_) => {
+                self.showing_tomorrow = !self.showing_tomorrow;
+                self.selected = 0;
+                true
+            }
+            InputKind::Filter => {
+                self.show_unavailable = !self.show_unavailable;
+                self.selected = self.selected.min(self.daily_ids().len().saturating_sub(1));
+                true
+            }
+            // Only today's dailies can be marked as done
+            InputKind::Select if !self.showing_tomorrow => {
+                if let Some(id) = self.selected_id() {
+                    self.app_state.toggle_daily_done(id);
+                }
+                true
+            }
+            _ => false,
+        }
     }
 
     fn handle_event(&mut self, event: &Event) {
         match event {
             Event::AchievementsLoaded(all_achievements) => {
                 self.achievements = all_achievements
-                    .into_iter()
+                    .iter()
                     .map(|achievement| (achievement.id, achievement.to_owned()))
                     .collect()
             }
+            Event::AccountAchievementsLoaded(all_account_achievements) => {
+                self.account_achievements = all_account_achievements
+                    .0
+                    .iter()
+                    .map(|account_achievement| {
+                        (account_achievement.id, account_achievement.to_owned())
+                    })
+                    .collect()
+            }
+            Event::AccountLoaded(account) => {
+                self.account = Some(account.to_owned());
+            }
+            Event::CharactersLoaded(characters) => {
+                self.characters = characters.to_owned();
+            }
             Event::FetchedDailies(dailies) => {
                 self.dailies = Some(dailies.to_owned());
             }
+            Event::FetchedTomorrowDailies(dailies) => {
+                self.tomorrow_dailies = Some(dailies.to_owned());
+            }
+            Event::DailyChecklistLoaded(checklist) => {
+                self.checklist = checklist.to_owned();
+            }
+            Event::DailyChecklistDone(done) => {
+                self.checklist_done = Some((done.to_owned(), Utc::now()));
+            }
             _ => {}
         }
     }
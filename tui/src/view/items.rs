@@ -0,0 +1,219 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::Stdout,
+};
+
+use tokio::sync::mpsc::UnboundedSender;
+use tui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::Text,
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+
+use crate::{
+    component::item_info::ItemInfo,
+    input::{InputEvent, InputKind},
+    widget::{
+        list_selection::{CursorMovement, ListSelection},
+        text_box::{Textbox, TextboxState},
+    },
+};
+use orrient::{
+    api::Item,
+    events::{Event, ItemDetails},
+    fetch::Request,
+};
+
+use super::View;
+
+pub struct ItemsView {
+    tx_request: UnboundedSender<Request>,
+    list_state: ListState,
+    textbox_state: TextboxState,
+    results: Vec<usize>,
+    items: HashMap<usize, Item>,
+    details: HashMap<usize, ItemDetails>,
+    requested_details: HashSet<usize>,
+    searching: bool,
+}
+
+impl ItemsView {
+    pub fn new(tx_request: UnboundedSender<Request>) -> Self {
+        Self {
+            tx_request,
+            list_state: ListState::default(),
+            textbox_state: TextboxState::default(),
+            results: Vec::default(),
+            items: HashMap::default(),
+            details: HashMap::default(),
+            requested_details: HashSet::default(),
+            searching: false,
+        }
+    }
+
+    fn selected_id(&self) -> Option<usize> {
+        self.list_state
+            .selected()
+            .and_then(|selected_index| self.results.get(selected_index).copied())
+    }
+
+    fn search(&mut self) {
+        let query = self.textbox_state.content().trim().to_string();
+        if !query.is_empty() {
+            let _ = self.tx_request.send(Request::SearchItems(query));
+        }
+    }
+
+    // Ask for the prices and recipes of the selected item, unless they're already on the way.
+    // They're asked for again each time the item is selected so the price stays current.
+    fn request_selected_details(&mut self) {
+        if let Some(id) = self.selected_id() {
+            if self.requested_details.insert(id) {
+                let _ = self.tx_request.send(Request::ItemDetails(id));
+            }
+        }
+    }
+
+    fn new_list_item<'a>(item: &Item) -> ListItem<'a> {
+        ListItem::new(Text::styled(
+            item.name.clone(),
+            Style::default().fg(ItemInfo::rarity_color(&item.rarity)),
+        ))
+    }
+}
+
+impl View for ItemsView {
+    fn name(&self) -> &'static str {
+        "Items"
+    }
+
+    fn draw(&mut self, frame: &mut Frame<CrosstermBackend<Stdout>>, area: Rect) {
+        let horiz_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(50), Constraint::Percentage(100)])
+            .split(area);
+
+        let left_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Percentage(100)])
+            .split(horiz_layout[0]);
+
+        frame.render_stateful_widget(
+            Textbox::new()
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM | Borders::RIGHT)
+                        .title("Search items by name or ID"),
+                )
+                .style(if self.searching {
+                    Style::default()
+                } else {
+                    Style::default().add_modifier(Modifier::DIM)
+                }),
+            left_layout[0],
+            &mut self.textbox_state,
+        );
+
+        frame.render_stateful_widget(
+            List::new(
+                self.results
+                    .iter()
+                    .filter_map(|id| self.items.get(id))
+                    .map(Self::new_list_item)
+                    .collect::<Vec<ListItem>>(),
+            )
+            .block(Block::default().borders(Borders::RIGHT))
+            .highlight_symbol(">>"),
+            left_layout[1],
+            &mut self.list_state,
+        );
+
+        if let Some(item) = self.selected_id().and_then(|id| self.items.get(&id)) {
+            frame.render_widget(
+                ItemInfo::new_widget(item, self.details.get(&item.id), &self.items),
+                horiz_layout[1],
+            );
+        }
+    }
+
+    fn handle_input(&mut self, event: &InputEvent) -> bool {
+        if self.searching {
+            match event.input {
+                InputKind::Confirm => {
+                    self.searching = false;
+                    self.search();
+                    return true;
+                }
+                InputKind::Back => {
+                    self.searching = false;
+                    return true;
+                }
+                _ => {}
+            }
+
+            return self.textbox_state.handle_input(event);
+        }
+
+        match event.input {
+            InputKind::Search => {
+                self.searching = true;
+                true
+            }
+            InputKind::MoveUp(amount) => {
+                self.list_state
+                    .move_cursor(self.results.len(), CursorMovement::Up(amount));
+                self.request_selected_details();
+                true
+            }
+            InputKind::MoveDown(amount) => {
+                self.list_state
+                    .move_cursor(self.results.len(), CursorMovement::Down(amount));
+                self.request_selected_details();
+                true
+            }
+            InputKind::Top => {
+                self.list_state.select(None);
+                self.list_state
+                    .move_cursor(self.results.len(), CursorMovement::None);
+                self.request_selected_details();
+                true
+            }
+            InputKind::Bottom => {
+                self.list_state
+                    .move_cursor(self.results.len(), CursorMovement::Down(u16::MAX));
+                self.request_selected_details();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn handle_event(&mut self, event: &Event) {
+        match event {
+            Event::ItemsFound(items) => {
+                let mut items = items.clone();
+                items.sort_by(|a, b| a.name.cmp(&b.name));
+                self.results = items.iter().map(|item| item.id).collect();
+                self.items
+                    .extend(items.into_iter().map(|item| (item.id, item)));
+                self.list_state.select(None);
+                self.list_state
+                    .move_cursor(self.results.len(), CursorMovement::None);
+                self.request_selected_details();
+            }
+            Event::ShowItem(id) => {
+                let _ = self.tx_request.send(Request::SearchItems(id.to_string()));
+            }
+            Event::ItemDetailsLoaded(details) => {
+                self.items
+                    .extend(details.items.iter().map(|item| (item.id, item.to_owned())));
+                self.requested_details.remove(&details.id);
+                self.details.insert(details.id, details.to_owned());
+            }
+            _ => {}
+        }
+    }
+}
@@ -2,6 +2,7 @@
 pub mod achievements;
 pub mod bookmarks;
 pub mod dailies;
+pub mod items;
 pub mod status;
 pub mod timer;
 pub mod tracks;
@@ -1,11 +1,18 @@
-use std::{io::Stdout, time::Duration};
+use std::{
+    io::{Stdout, Write},
+    time::{Duration, Instant},
+};
 
 use futures::FutureExt;
-use orrient::events::Event;
+use orrient::{
+    events::Event,
+    reset::{self, Reset},
+};
 use tokio::{sync::mpsc::UnboundedSender, task::JoinHandle};
 use tui::{
     backend::CrosstermBackend,
-    layout::Rect,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
@@ -16,16 +23,24 @@ use super::View;
 
 pub struct StatusView {
     message: String,
+    /// Kept apart from `message` so later status messages don't hide it
+    alert: Option<String>,
+    /// When the alert was shown, so it can flash in time with the render tick
+    alert_shown: Instant,
     tx_event: UnboundedSender<Event>,
     status_timeout_handle: Option<JoinHandle<()>>,
+    alert_timeout_handle: Option<JoinHandle<()>>,
 }
 
 impl StatusView {
     pub fn new(tx_event: UnboundedSender<Event>) -> Self {
         StatusView {
             message: String::default(),
+            alert: None,
+            alert_shown: Instant::now(),
             tx_event,
             status_timeout_handle: None,
+            alert_timeout_handle: None,
         }
     }
 }
@@ -35,7 +50,22 @@ impl StatusView {
         if let Some(handle) = &self.status_timeout_handle {
             handle.abort();
         }
-        self.status_timeout_handle = Some(status_timeout(self.tx_event.clone()));
+        self.status_timeout_handle = Some(status_timeout(
+            self.tx_event.clone(),
+            Duration::from_secs(5),
+            Event::ClearStatusMessage,
+        ));
+    }
+
+    fn start_alert_timeout(&mut self) {
+        if let Some(handle) = &self.alert_timeout_handle {
+            handle.abort();
+        }
+        self.alert_timeout_handle = Some(status_timeout(
+            self.tx_event.clone(),
+            Duration::from_secs(60),
+            Event::ClearAlert,
+        ));
     }
 }
 
@@ -45,9 +75,49 @@ impl View for StatusView {
     }
 
     fn draw(&mut self, frame: &mut Frame<CrosstermBackend<Stdout>>, area: Rect) {
+        let alert_width = self
+            .alert
+            .as_ref()
+            .map_or(0, |alert| alert.chars().count() as u16 + 1);
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(alert_width),
+                Constraint::Percentage(100),
+                Constraint::Length(52),
+            ])
+            .split(area);
+        if let Some(alert) = &self.alert {
+            // The colors are swapped every other second so it flashes
+            let style = Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD);
+            let style = if self.alert_shown.elapsed().as_secs() % 2 == 0 {
+                style.add_modifier(Modifier::REVERSED)
+            } else {
+                style
+            };
+            frame.render_widget(
+                Paragraph::new(alert.as_str())
+                    .style(style)
+                    .block(Block::default().borders(Borders::TOP)),
+                chunks[0],
+            );
+        }
         frame.render_widget(
             Paragraph::new(self.message.as_str()).block(Block::default().borders(Borders::TOP)),
-            area,
+            chunks[1],
+        );
+        frame.render_widget(
+            Paragraph::new(format!(
+                "Daily reset {}  Weekly reset {}",
+                reset::format_countdown(Reset::Daily.until_next()),
+                reset::format_countdown(Reset::Weekly.until_next())
+            ))
+            .style(Style::default().add_modifier(Modifier::DIM))
+            .alignment(Alignment::Right)
+            .block(Block::default().borders(Borders::TOP)),
+            chunks[2],
         );
     }
 
@@ -61,17 +131,32 @@ impl View for StatusView {
                 self.message = message.clone();
                 self.start_timeout();
             }
+            Event::Alert(message) => {
+                self.alert = Some(message.clone());
+                self.alert_shown = Instant::now();
+                self.start_alert_timeout();
+                // Ring the terminal bell
+                let mut stdout = std::io::stdout();
+                let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+            }
             Event::ClearStatusMessage => {
                 self.message.clear();
             }
+            Event::ClearAlert => {
+                self.alert = None;
+            }
             _ => {}
         }
     }
 }
 
-fn status_timeout(tx_event: UnboundedSender<Event>) -> JoinHandle<()> {
+fn status_timeout(
+    tx_event: UnboundedSender<Event>,
+    timeout: Duration,
+    clear_event: Event,
+) -> JoinHandle<()> {
     tokio::spawn(async move {
-        tokio::time::sleep(Duration::from_secs(5)).fuse().await;
-        let _ = tx_event.send(Event::ClearStatusMessage);
+        tokio::time::sleep(timeout).fuse().await;
+        let _ = tx_event.send(clear_event);
     })
 }
@@ -1,37 +1,68 @@
-use std::{io::Stdout, iter, ops::Sub};
+use std::{io::Stdout, iter, ops::Sub, rc::Rc};
 
 use chrono::{Duration, Timelike, Utc};
+use copypasta::{ClipboardContext, ClipboardProvider};
 use gw2timers::{
     category::Category,
     event::EventInstance,
     meta::{MapMeta, MapMetaKind},
 };
+use log::debug;
+use tokio::sync::mpsc::UnboundedSender;
 use tui::{
     backend::CrosstermBackend,
     layout::{Constraint, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Cell, Row, Table},
+    widgets::{Cell, Row, Table, TableState},
     Frame,
 };
 
-use crate::input::InputEvent;
-use orrient::events::Event;
+use crate::{
+    input::{InputEvent, InputKind},
+    widget::list_selection::{CursorMovement, ListSelection},
+};
+use orrient::{events::Event, state::AppState};
 
 use super::View;
 
 pub struct TimerView {
+    app_state: Rc<AppState>,
+    tx_event: UnboundedSender<Event>,
+    clipboard_ctx: Option<ClipboardContext>,
+    table_state: TableState,
+    selected_event: usize,
+    num_events: u16,
     heading_width: u16,
     event_width: u16,
 }
 
 impl TimerView {
-    pub fn new() -> Self {
+    pub fn new(app_state: Rc<AppState>, tx_event: UnboundedSender<Event>) -> Self {
+        let clipboard_ctx = match ClipboardContext::new() {
+            Ok(ctx) => Some(ctx),
+            Err(err) => {
+                debug!("Could not load clipboard context: {}.", err);
+                None
+            }
+        };
         Self {
+            app_state,
+            tx_event,
+            clipboard_ctx,
+            table_state: TableState::default(),
+            selected_event: 0,
+            num_events: 0,
             heading_width: 19,
             event_width: 40,
         }
     }
 
+    fn selected_meta(&self) -> Option<MapMetaKind> {
+        self.table_state.selected().and_then(|selected_index| {
+            IntoIterator::into_iter(MapMetaKind::all_keys()).nth(selected_index)
+        })
+    }
+
     fn meta_color(meta: MapMeta) -> Color {
         match meta.category {
             Category::CoreTyria => Color::LightBlue,
@@ -41,6 +72,7 @@ impl TimerView {
             Category::HeartOfThorns => Color::Green,
             Category::PathOfFire => Color::LightYellow,
             Category::TheIcebroodSaga => Color::Blue,
+            Category::EndOfDragons => Color::LightCyan,
         }
     }
 
@@ -60,14 +92,38 @@ impl TimerView {
         event_instance: EventInstance,
         color: Color,
         current_time_offset: Duration,
+        selected: bool,
     ) -> Cell<'a> {
         let time_until_event = event_instance.start_time.sub(current_time_offset);
+        let style = if selected {
+            Style::default().fg(color).add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().fg(color)
+        };
         Cell::from(format!(
             "{} - {}",
             Self::time_until_fmt(time_until_event),
             event_instance.schedule.name
         ))
-        .style(Style::default().fg(color))
+        .style(style)
+    }
+
+    // The events shown in a row of the table, starting with the one that's currently active
+    fn meta_events(meta: &MapMetaKind, time: Duration, mut num_events: u16) -> Vec<EventInstance> {
+        let mut meta_iter = meta.into_iter().fast_forward(time);
+
+        let first_column = if let Some(event_instance) = meta_iter.now() {
+            num_events -= 1;
+            event_instance
+        } else {
+            meta_iter.next().unwrap()
+        };
+
+        let mut events = meta_iter
+            .take(num_events as usize)
+            .collect::<Vec<EventInstance>>();
+        events.insert(0, first_column);
+        events
     }
 
     fn new_meta_row<'a>(
@@ -75,32 +131,58 @@ impl TimerView {
         time: Duration,
         name: &'static str,
         color: Color,
-        mut num_events: u16,
+        num_events: u16,
+        alert: bool,
+        selected_event: Option<usize>,
     ) -> Row<'a> {
-        let mut meta_iter = meta.into_iter().fast_forward(time);
-
-        let heading = Cell::from(name).style(
+        let heading = if alert {
+            format!("* {}", name)
+        } else {
+            name.to_string()
+        };
+        let heading = Cell::from(heading).style(
             Style::default()
                 .bg(Color::Black)
                 .fg(color)
                 .add_modifier(Modifier::BOLD | Modifier::REVERSED),
         );
 
-        let first_column = if let Some(event_instance) = meta_iter.now() {
-            num_events -= 1;
-            Self::new_event_cell(event_instance, color, time)
-        } else {
-            Self::new_event_cell(meta_iter.next().unwrap(), color, time)
-        };
-
-        let mut remaining_columns = meta_iter
-            .take(num_events as usize)
-            .map(|e| Self::new_event_cell(e, color, time))
+        let mut columns = Self::meta_events(meta, time, num_events)
+            .into_iter()
+            .enumerate()
+            .map(|(index, e)| Self::new_event_cell(e, color, time, selected_event == Some(index)))
             .collect::<Vec<Cell>>();
-        remaining_columns.insert(0, heading);
-        remaining_columns.insert(1, first_column);
+        columns.insert(0, heading);
+
+        Row::new(columns)
+    }
+
+    fn copy_selected_waypoint(&mut self) {
+        let meta_key = match self.selected_meta() {
+            Some(meta_key) => meta_key,
+            None => return,
+        };
+        let current_time = Utc::now().time();
+        let time = Duration::seconds(current_time.num_seconds_from_midnight() as i64);
+        let event_instance = match Self::meta_events(&meta_key, time, self.num_events)
+            .into_iter()
+            .nth(self.selected_event)
+        {
+            Some(event_instance) => event_instance,
+            None => return,
+        };
 
-        Row::new(remaining_columns)
+        let name = event_instance.schedule.name;
+        let message = match (waypoint(&meta_key, name), self.clipboard_ctx.as_mut()) {
+            (Some(waypoint), Some(clipboard)) => match clipboard.set_contents(waypoint.to_string())
+            {
+                Ok(_) => format!("Copied waypoint for '{}' to clipboard.", name),
+                Err(err) => format!("Could not copy waypoint for '{}': {}", name, err),
+            },
+            (Some(_), None) => "Clipboard is not available.".to_string(),
+            (None, _) => format!("No waypoint known for '{}'.", name),
+        };
+        let _ = self.tx_event.send(Event::StatusMessage(message));
     }
 }
 
@@ -111,6 +193,11 @@ impl View for TimerView {
 
     fn draw(&mut self, frame: &mut Frame<CrosstermBackend<Stdout>>, area: Rect) {
         let num_events = area.width / self.event_width;
+        self.num_events = num_events;
+        self.selected_event = self
+            .selected_event
+            .min((num_events as usize).saturating_sub(1));
+        let selected_row = self.table_state.selected();
         let event_area_width = area.width - self.heading_width;
         let mut constaints = iter::repeat(Constraint::Length(event_area_width / num_events))
             .take(num_events as usize)
@@ -119,30 +206,175 @@ impl View for TimerView {
 
         let current_time = Utc::now().time();
         let time = Duration::seconds(current_time.num_seconds_from_midnight() as i64);
-        frame.render_widget(
+        frame.render_stateful_widget(
             Table::new(
                 MapMetaKind::all_keys()
                     .iter()
-                    .map(|meta_key| {
+                    .enumerate()
+                    .map(|(row, meta_key)| {
                         let meta = meta_key.info();
+                        let alert = self.app_state.has_alert(meta.name);
                         Self::new_meta_row(
                             meta_key,
                             time,
                             meta.name,
                             Self::meta_color(meta),
                             num_events,
+                            alert,
+                            Some(self.selected_event).filter(|_| selected_row == Some(row)),
                         )
                     })
                     .collect::<Vec<Row>>(),
             )
-            .widths(&constaints),
+            .widths(&constaints)
+            .highlight_style(Style::default().add_modifier(Modifier::UNDERLINED)),
             area,
+            &mut self.table_state,
         );
     }
 
-    fn handle_input(&mut self, _: &InputEvent) -> bool {
-        false
+    fn handle_input(&mut self, event: &InputEvent) -> bool {
+        let len = MapMetaKind::all_keys().len();
+        match event.input {
+            InputKind::MoveUp(amount) => {
+                self.table_state
+                    .move_cursor(len, CursorMovement::Up(amount));
+                true
+            }
+            InputKind::MoveDown(amount) => {
+                self.table_state
+                    .move_cursor(len, CursorMovement::Down(amount));
+                true
+            }
+            InputKind::MoveLeft(amount) => {
+                self.selected_event = self.selected_event.saturating_sub(amount as usize);
+                true
+            }
+            InputKind::MoveRight(amount) => {
+                self.selected_event = (self.selected_event + amount as usize)
+                    .min((self.num_events as usize).saturating_sub(1));
+                true
+            }
+            InputKind::Confirm => {
+                self.copy_selected_waypoint();
+                true
+            }
+            InputKind::Select => {
+                if let Some(meta_key) = self.selected_meta() {
+                    self.app_state.toggle_alert(meta_key.info().name);
+                }
+                true
+            }
+            _ => false,
+        }
     }
 
     fn handle_event(&mut self, _: &Event) {}
 }
+
+// Waypoint chat links for events, by the meta and the name they are scheduled under. Events
+// without a waypoint of their own fall back to the waypoint for the meta's map. The day and night
+// cycles and PvP tournaments don't take place anywhere in particular, so they have none.
+fn waypoint(meta: &MapMetaKind, event_name: &str) -> Option<&'static str> {
+    let event_waypoint = match (meta, event_name) {
+        (MapMetaKind::WorldBosses, "Admiral Taidha Covington") => Some("[&BKgBAAA=]"),
+        (MapMetaKind::WorldBosses, "Claw of Jormag") => Some("[&BHoCAAA=]"),
+        (MapMetaKind::WorldBosses, "Fire Elemental") => Some("[&BEcAAAA=]"),
+        (MapMetaKind::WorldBosses, "Golem Mark II") => Some("[&BNQCAAA=]"),
+        (MapMetaKind::WorldBosses, "Great Jungle Wurm") => Some("[&BEEFAAA=]"),
+        (MapMetaKind::WorldBosses, "Megadestroyer") => Some("[&BM0CAAA=]"),
+        (MapMetaKind::WorldBosses, "Modniir Ulgoth") => Some("[&BLEAAAA=]"),
+        (MapMetaKind::WorldBosses, "Shadow Behemoth") => Some("[&BPcAAAA=]"),
+        (MapMetaKind::WorldBosses, "Svanir Shaman Chief") => Some("[&BMIDAAA=]"),
+        (MapMetaKind::WorldBosses, "The Shatterer") => Some("[&BE4DAAA=]"),
+        (MapMetaKind::HardWorldBosses, "Karka Queen") => Some("[&BNUGAAA=]"),
+        (MapMetaKind::HardWorldBosses, "Tequatl the Sunless") => Some("[&BNABAAA=]"),
+        (MapMetaKind::HardWorldBosses, "Triple Trouble") => Some("[&BKoBAAA=]"),
+        (MapMetaKind::LeyLineAnomaly, "Gendarran Fields") => Some("[&BOQAAAA=]"),
+        (MapMetaKind::LeyLineAnomaly, "Iron Marches") => Some("[&BOcBAAA=]"),
+        (MapMetaKind::LeyLineAnomaly, "Timberline Falls") => Some("[&BEwCAAA=]"),
+        (MapMetaKind::LakeDoric, "New Loamhurst") => Some("[&BKYJAAA=]"),
+        (MapMetaKind::LakeDoric, "Noran's Homestead") => Some("[&BK8JAAA=]"),
+        (MapMetaKind::LakeDoric, "Saidra's Haven") => Some("[&BK0JAAA=]"),
+        (MapMetaKind::ElonRiverlands, "Doppelganger") => Some("[&BFMKAAA=]"),
+        (MapMetaKind::TheDesolation, "Junudu Rising") => Some("[&BMEKAAA=]"),
+        (MapMetaKind::DomainOfVabbi, "Forged with Fire") => Some("[&BO0KAAA=]"),
+        (MapMetaKind::JahaiBluffs, "Death-Branded Shatterer") => Some("[&BJMLAAA=]"),
+        (MapMetaKind::ThunderheadPeaks, "The Oil Floes") => Some("[&BKYLAAA=]"),
+        (MapMetaKind::GrothmarValley, "Ooze Pits")
+        | (MapMetaKind::GrothmarValley, "Metal Concert") => Some("[&BPgLAAA=]"),
+        (MapMetaKind::BjoraMarches, "Raven Shrines") => Some("[&BEQMAAA=]"),
+        (MapMetaKind::BjoraMarches, "Shards and Construct") => Some("[&BMIMAAA=]"),
+        (MapMetaKind::TheEchovaldWilds, "Aspenwood") => Some("[&BPkMAAA=]"),
+        _ => None,
+    };
+    event_waypoint.or_else(|| map_waypoint(meta))
+}
+
+// The waypoint most of a meta's events are reached from
+fn map_waypoint(meta: &MapMetaKind) -> Option<&'static str> {
+    match meta {
+        // Every event in these has a waypoint of its own, or no location at all
+        MapMetaKind::DayAndNight
+        | MapMetaKind::Cantha
+        | MapMetaKind::PVPTournaments
+        | MapMetaKind::WorldBosses
+        | MapMetaKind::HardWorldBosses
+        | MapMetaKind::LeyLineAnomaly => None,
+        MapMetaKind::TwistedMarionette | MapMetaKind::Dragonstorm => Some("[&BAkMAAA=]"),
+        MapMetaKind::DryTop => Some("[&BIcHAAA=]"),
+        MapMetaKind::VerdantBrink => Some("[&BAgIAAA=]"),
+        MapMetaKind::AuricBasin => Some("[&BAIIAAA=]"),
+        MapMetaKind::TangledDepths => Some("[&BPUHAAA=]"),
+        MapMetaKind::DragonsStand => Some("[&BBAIAAA=]"),
+        MapMetaKind::LakeDoric => Some("[&BK8JAAA=]"),
+        MapMetaKind::CrystalOasis => Some("[&BLsKAAA=]"),
+        MapMetaKind::DesertHighlands => Some("[&BGsKAAA=]"),
+        MapMetaKind::ElonRiverlands => Some("[&BL4KAAA=]"),
+        MapMetaKind::TheDesolation => Some("[&BKMKAAA=]"),
+        MapMetaKind::DomainOfVabbi => Some("[&BHQKAAA=]"),
+        MapMetaKind::DomainOfIstan => Some("[&BAkLAAA=]"),
+        MapMetaKind::JahaiBluffs => Some("[&BIMLAAA=]"),
+        MapMetaKind::ThunderheadPeaks => Some("[&BLsLAAA=]"),
+        MapMetaKind::GrothmarValley => Some("[&BA4MAAA=]"),
+        MapMetaKind::BjoraMarches => Some("[&BDkMAAA=]"),
+        MapMetaKind::SeitungProvince => Some("[&BGUNAAA=]"),
+        MapMetaKind::NewKainengCity => Some("[&BBkNAAA=]"),
+        MapMetaKind::TheEchovaldWilds => Some("[&BMwMAAA=]"),
+        MapMetaKind::DragonsEnd => Some("[&BKIMAAA=]"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_scheduled_event_has_a_waypoint() {
+        for meta_key in IntoIterator::into_iter(MapMetaKind::all_keys()) {
+            if let MapMetaKind::DayAndNight | MapMetaKind::Cantha | MapMetaKind::PVPTournaments =
+                meta_key
+            {
+                continue;
+            }
+            for schedule in meta_key.info().schedules {
+                assert!(
+                    waypoint(&meta_key, schedule.name).is_some(),
+                    "No waypoint for '{}' in '{}'",
+                    schedule.name,
+                    meta_key.info().name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn events_without_a_location_have_no_waypoint() {
+        assert_eq!(waypoint(&MapMetaKind::DayAndNight, "Day"), None);
+        assert_eq!(waypoint(&MapMetaKind::Cantha, "Night"), None);
+        assert_eq!(
+            waypoint(&MapMetaKind::PVPTournaments, "Lyssa's Legions"),
+            None
+        );
+    }
+}
@@ -12,9 +12,9 @@ use std::{collections::HashMap, io::Stdout, rc::Rc};
 
 use orrient::{
     api::{AccountAchievement, Achievement},
-    events::Event,
+    events::{Event, Rewards},
     state::AppState,
-    tracks::Track,
+    tracks::{CustomTrack, Track},
 };
 
 use tokio::sync::mpsc::UnboundedSender;
@@ -22,7 +22,9 @@ use tokio::sync::mpsc::UnboundedSender;
 use tui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
-    widgets::{Block, Borders, List, ListItem, ListState},
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
     Frame,
 };
 
@@ -35,8 +37,12 @@ pub struct TracksView {
     tier_progress_bar_height: u16,
     achievements: HashMap<usize, Achievement>,
     account_achievements: HashMap<usize, AccountAchievement>,
+    rewards: Rewards,
     tracks: Vec<Track>,
     add_track_popup: CustomTrackPopupState,
+    /// Whether the cursor is on the progress and checklist of a custom track
+    details_focused: bool,
+    details_state: ListState,
 }
 
 impl TracksView {
@@ -52,8 +58,11 @@ impl TracksView {
             tier_progress_bar_height: 1,
             achievements: HashMap::default(),
             account_achievements: HashMap::default(),
+            rewards: Rewards::default(),
             tracks,
             add_track_popup,
+            details_focused: false,
+            details_state: ListState::default(),
         }
     }
 
@@ -69,8 +78,8 @@ impl TracksView {
         match track {
             Track::Achievement(id) => {
                 let account_achievement = self.account_achievements.get(id);
-                let current = account_achievement.map(|aa| aa.current).flatten();
-                let max = account_achievement.map(|aa| aa.max).flatten();
+                let current = account_achievement.and_then(|aa| aa.current);
+                let max = account_achievement.and_then(|aa| aa.max);
                 let percent_complete = if let (Some(current), Some(max)) = (current, max) {
                     Some(((current as f64) / (max as f64) * 100f64) as u16)
                 } else {
@@ -92,10 +101,171 @@ impl TracksView {
                     ),
                 )
             }
-            Track::Custom(item) => ListItem::new(format!("       {}", item)),
+            Track::Custom(custom) => {
+                let name = custom
+                    .ratio()
+                    .map_or(format!("       {}", custom.name), |ratio| {
+                        format!("({:>3}%) {}", (ratio * 100f64) as u16, custom.name)
+                    });
+                let due = match custom.due {
+                    Some(due) if !custom.is_done() => Span::styled(
+                        format!(" (due {})", due),
+                        if custom.is_overdue() {
+                            Style::default().fg(Color::Red)
+                        } else {
+                            Style::default().fg(Color::DarkGray)
+                        },
+                    ),
+                    _ => Span::raw(""),
+                };
+                ListItem::new(Spans::from(vec![Span::raw(name), due]))
+            }
         }
     }
 
+    fn selected_custom(&self) -> Option<CustomTrack> {
+        match self.selected_track() {
+            Some(Track::Custom(custom)) => Some(custom),
+            _ => None,
+        }
+    }
+
+    /// The rows that can be selected in the details of a custom track: the progress first, if
+    /// there is any, then the checklist
+    fn details_len(custom: &CustomTrack) -> usize {
+        custom.checklist.len() + custom.progress.map_or(0, |_| 1)
+    }
+
+    fn draw_custom_info<B: tui::backend::Backend>(
+        &mut self,
+        custom: &CustomTrack,
+        frame: &mut Frame<B>,
+        area: Rect,
+    ) {
+        let progress_height = custom.progress.map_or(0, |_| self.tier_progress_bar_height);
+        let info_chunks: Vec<Rect> = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(progress_height),
+                Constraint::Length(2),
+                Constraint::Percentage(100),
+            ])
+            .split(area);
+
+        let selected = Some(self.details_state.selected().unwrap_or_default())
+            .filter(|_| self.details_focused);
+
+        if let Some(progress) = custom.progress {
+            let current = custom.current();
+            let label = format!(
+                "{}{}/{}",
+                if selected == Some(0) { ">> " } else { "" },
+                current,
+                progress.max
+            );
+            frame.render_widget(
+                AchievementProgressInfo::gauge(
+                    label,
+                    current.min(progress.max) as f64 / progress.max as f64,
+                ),
+                info_chunks[0],
+            );
+        }
+
+        let mut info = vec![];
+        info.push(if custom.is_done() {
+            Span::styled("Done", Style::default().fg(Color::Green))
+        } else {
+            Span::raw("Not done")
+        });
+        if let Some(due) = custom.due {
+            info.push(Span::styled(
+                format!("  Due {}", due),
+                if custom.is_overdue() {
+                    Style::default().fg(Color::Red)
+                } else {
+                    Style::default()
+                },
+            ));
+        }
+        if let Some(recurrence) = custom.recurrence {
+            info.push(Span::raw(format!("  Repeats {}", recurrence.name())));
+        }
+        frame.render_widget(Paragraph::new(Spans::from(info)), info_chunks[1]);
+
+        let offset = custom.progress.map_or(0, |_| 1);
+        let mut checklist_state = ListState::default();
+        checklist_state.select(
+            selected
+                .filter(|selected| *selected >= offset)
+                .map(|selected| selected - offset),
+        );
+        frame.render_stateful_widget(
+            List::new(
+                custom
+                    .checklist
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| {
+                        if custom.is_checked(index) {
+                            ListItem::new(format!("[x] {}", item.name))
+                                .style(Style::default().fg(Color::Green))
+                        } else {
+                            ListItem::new(format!("[ ] {}", item.name))
+                        }
+                    })
+                    .collect::<Vec<ListItem>>(),
+            )
+            .highlight_symbol(">> "),
+            info_chunks[2],
+            &mut checklist_state,
+        );
+    }
+
+    fn handle_input_details(&mut self, event: &InputEvent) -> bool {
+        let custom = match self.selected_custom() {
+            Some(custom) => custom,
+            None => {
+                self.details_focused = false;
+                return false;
+            }
+        };
+        let selected = self.details_state.selected().unwrap_or_default();
+        let on_progress = custom.progress.is_some() && selected == 0;
+        let checklist_index = selected - custom.progress.map_or(0, |_| 1).min(selected);
+
+        match event.input {
+            InputKind::MoveUp(amount) => {
+                self.details_state
+                    .move_cursor(Self::details_len(&custom), CursorMovement::Up(amount));
+            }
+            InputKind::MoveDown(amount) => {
+                self.details_state
+                    .move_cursor(Self::details_len(&custom), CursorMovement::Down(amount));
+            }
+            InputKind::MoveRight(amount) if on_progress => {
+                self.app_state
+                    .update_custom_track(&custom.name, |custom| custom.add_progress(amount as i64));
+            }
+            InputKind::MoveLeft(amount) if on_progress => {
+                self.app_state.update_custom_track(&custom.name, |custom| {
+                    custom.add_progress(-(amount as i64))
+                });
+            }
+            InputKind::Select | InputKind::Confirm if !on_progress => {
+                self.app_state.update_custom_track(&custom.name, |custom| {
+                    custom.toggle_checked(checklist_index)
+                });
+            }
+            InputKind::MoveLeft(_) | InputKind::Back => {
+                self.details_focused = false;
+            }
+            _ => return false,
+        }
+        self.tracks = self.app_state.tracked_items().into_iter().collect();
+        true
+    }
+
     fn draw_achievement_info<B: tui::backend::Backend>(
         &mut self,
         achievement_id: usize,
@@ -113,8 +283,8 @@ impl TracksView {
                 ])
                 .split(area);
 
-            self.draw_progress(frame, info_chunks[0], &achievement, account_achievement);
-            self.draw_info(frame, info_chunks[1], &achievement, account_achievement)
+            self.draw_progress(frame, info_chunks[0], achievement, account_achievement);
+            self.draw_info(frame, info_chunks[1], achievement, account_achievement)
         }
     }
 
@@ -167,7 +337,7 @@ impl TracksView {
         account_achievement: Option<&AccountAchievement>,
     ) {
         frame.render_widget(
-            AchievementInfo::new_widget(achievement, account_achievement),
+            AchievementInfo::new_widget(achievement, account_achievement, &self.rewards),
             area,
         );
     }
@@ -189,7 +359,7 @@ impl View for TracksView {
         if let Some(track) = self.selected_track() {
             match track {
                 Track::Achievement(id) => self.draw_achievement_info(id, frame, h_chunks[1]),
-                Track::Custom(_) => {}
+                Track::Custom(custom) => self.draw_custom_info(&custom, frame, h_chunks[1]),
             }
         }
 
@@ -201,6 +371,10 @@ impl View for TracksView {
             return true;
         }
 
+        if self.details_focused {
+            return self.handle_input_details(event);
+        }
+
         match event.input {
             InputKind::MoveUp(amount) => {
                 self.list_state.move_cursor(
@@ -222,10 +396,33 @@ impl View for TracksView {
                 }
                 true
             }
+            InputKind::Confirm => {
+                if let Some(custom) = self.selected_custom() {
+                    self.app_state
+                        .update_custom_track(&custom.name, CustomTrack::toggle_done);
+                    self.tracks = self.app_state.tracked_items().into_iter().collect();
+                }
+                true
+            }
+            InputKind::MoveRight(_) => {
+                if let Some(custom) = self.selected_custom() {
+                    if Self::details_len(&custom) > 0 {
+                        self.details_focused = true;
+                        self.details_state.select(Some(0));
+                    }
+                }
+                true
+            }
             InputKind::New => {
                 self.add_track_popup.active(true);
                 true
             }
+            InputKind::Edit => {
+                if let Some(track @ Track::Custom(_)) = self.selected_track() {
+                    self.add_track_popup.edit(track);
+                }
+                true
+            }
             _ => false,
         }
     }
@@ -234,7 +431,7 @@ impl View for TracksView {
         match event {
             Event::AchievementsLoaded(all_achievements) => {
                 self.achievements = all_achievements
-                    .into_iter()
+                    .iter()
                     .map(|achievement| (achievement.id, achievement.to_owned()))
                     .collect();
             }
@@ -250,6 +447,17 @@ impl View for TracksView {
             Event::ToggleTrack(_) | Event::AddTrack(_) => {
                 self.tracks = self.app_state.tracked_items().into_iter().collect();
             }
+            Event::ReplaceTrack(track, new_track) => {
+                if !self.app_state.replace_track(track, new_track.clone()) {
+                    let _ = self.tx_event.send(Event::StatusMessage(
+                        "That item is already tracked.".to_string(),
+                    ));
+                    self.add_track_popup
+                        .reopen(track.clone(), new_track.clone());
+                }
+                self.tracks = self.app_state.tracked_items().into_iter().collect();
+            }
+            Event::RewardsLoaded(rewards) => self.rewards = rewards.to_owned(),
             _ => {}
         }
     }
@@ -1,10 +1,11 @@
 use std::{cell::Cell, iter};
 
+use chrono::NaiveDate;
 use tokio::sync::mpsc::UnboundedSender;
 use tui::{
     layout::{Constraint, Direction, Layout, Margin, Rect},
-    style::{Modifier, Style},
-    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
     Frame,
 };
 
@@ -14,12 +15,23 @@ use crate::widget::{
     list_selection::{CursorMovement, ListSelection},
     text_box::{Textbox, TextboxState},
 };
-use orrient::{events::Event, tracks::Track};
+use orrient::{
+    events::Event,
+    tracks::{ChecklistItem, CustomTrack, Progress, Recurrence, Track},
+};
+
+const FIELDS: [&str; 6] = ["Name", "Tasks", "Goal", "Due", "", ""];
 
 pub struct CustomTrackPopupState {
     textbox_state: TextboxState,
-    checkbox_state: CheckboxState,
+    checklist_textbox_state: TextboxState,
+    progress_textbox_state: TextboxState,
+    due_textbox_state: TextboxState,
+    daily_checkbox_state: CheckboxState,
+    weekly_checkbox_state: CheckboxState,
     list_state: ListState,
+    /// The track being edited, if not adding a new one
+    editing: Option<Track>,
     active: Cell<bool>,
     tx_event: UnboundedSender<Event>,
 }
@@ -28,8 +40,13 @@ impl CustomTrackPopupState {
     pub fn new(tx_event: UnboundedSender<Event>) -> Self {
         let mut popup = Self {
             textbox_state: TextboxState::default(),
-            checkbox_state: CheckboxState::default(),
+            checklist_textbox_state: TextboxState::default(),
+            progress_textbox_state: TextboxState::default(),
+            due_textbox_state: TextboxState::default(),
+            daily_checkbox_state: CheckboxState::default(),
+            weekly_checkbox_state: CheckboxState::default(),
             list_state: ListState::default(),
+            editing: None,
             active: Cell::new(false),
             tx_event,
         };
@@ -43,13 +60,127 @@ impl CustomTrackPopupState {
 
     fn reset(&mut self) {
         self.textbox_state = TextboxState::default();
-        self.checkbox_state = CheckboxState::default();
+        self.checklist_textbox_state = TextboxState::default();
+        self.progress_textbox_state = TextboxState::default();
+        self.due_textbox_state = TextboxState::default();
+        self.daily_checkbox_state = CheckboxState::default();
+        self.weekly_checkbox_state = CheckboxState::default();
         self.list_state = ListState::default();
         self.list_state.select(Some(0));
+        self.editing = None;
+    }
+
+    /// Open the popup filled in with an existing track
+    pub fn edit(&mut self, track: Track) {
+        self.reopen(track.clone(), track);
+    }
+
+    /// Open the popup to keep editing `track`, filled in with `edited` values that couldn't be
+    /// saved
+    pub fn reopen(&mut self, track: Track, edited: Track) {
+        self.reset();
+        if let Track::Custom(custom) = &edited {
+            self.textbox_state.set_content(custom.name.clone());
+            self.checklist_textbox_state.set_content(
+                custom
+                    .checklist
+                    .iter()
+                    .map(|item| item.name.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", "),
+            );
+            if let Some(progress) = custom.progress {
+                self.progress_textbox_state
+                    .set_content(progress.max.to_string());
+            }
+            if let Some(due) = custom.due {
+                self.due_textbox_state.set_content(due.to_string());
+            }
+            self.daily_checkbox_state
+                .set_checked(custom.recurrence == Some(Recurrence::Daily));
+            self.weekly_checkbox_state
+                .set_checked(custom.recurrence == Some(Recurrence::Weekly));
+        }
+        self.editing = Some(track);
+        self.active(true);
+    }
+
+    fn selected_textbox(&mut self) -> Option<&mut TextboxState> {
+        match self.list_state.selected() {
+            Some(0) => Some(&mut self.textbox_state),
+            Some(1) => Some(&mut self.checklist_textbox_state),
+            Some(2) => Some(&mut self.progress_textbox_state),
+            Some(3) => Some(&mut self.due_textbox_state),
+            _ => None,
+        }
+    }
+
+    /// The goal and due date, or why they can't be read
+    fn parse(&self) -> Result<(Option<usize>, Option<NaiveDate>), String> {
+        let progress = self.progress_textbox_state.content().trim();
+        let progress = if progress.is_empty() {
+            None
+        } else {
+            match progress.parse::<usize>() {
+                Ok(max) if max > 0 => Some(max),
+                _ => return Err("Goal must be a number above 0".to_string()),
+            }
+        };
+
+        let due = self.due_textbox_state.content().trim();
+        let due = if due.is_empty() {
+            None
+        } else {
+            match NaiveDate::parse_from_str(due, "%Y-%m-%d") {
+                Ok(due) => Some(due),
+                Err(_) => return Err("Due date must look like 2021-12-31".to_string()),
+            }
+        };
+
+        Ok((progress, due))
     }
 
-    fn finish(&mut self) -> Track {
-        let track = Track::Custom(self.textbox_state.take());
+    fn finish(&mut self, max: Option<usize>, due: Option<NaiveDate>) -> Track {
+        // Keep what was already done when editing a track
+        let previous = match self.editing.take() {
+            Some(Track::Custom(custom)) => custom,
+            _ => CustomTrack::default(),
+        };
+        // Tasks are entered as a comma separated list
+        let checklist = self
+            .checklist_textbox_state
+            .take()
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .map(|name| ChecklistItem {
+                done: previous
+                    .checklist
+                    .iter()
+                    .any(|item| item.name == name && item.done),
+                name,
+            })
+            .collect();
+        let recurrence = if self.daily_checkbox_state.checked() {
+            Some(Recurrence::Daily)
+        } else if self.weekly_checkbox_state.checked() {
+            Some(Recurrence::Weekly)
+        } else {
+            None
+        };
+        let track = Track::Custom(CustomTrack {
+            name: self.textbox_state.take(),
+            checklist,
+            progress: max.map(|max| Progress {
+                current: previous
+                    .progress
+                    .map_or(0, |progress| progress.current.min(max)),
+                max,
+            }),
+            due,
+            recurrence,
+            ..previous
+        });
         self.reset();
         track
     }
@@ -59,7 +190,7 @@ impl CustomTrackPopupState {
             return;
         }
 
-        let (width, height) = (50, 10);
+        let (width, height) = (50, 12);
         if area.width < width || area.height < height {
             return;
         }
@@ -71,7 +202,11 @@ impl CustomTrackPopupState {
         let area = Rect::new(x, y, width, height);
         let background = Block::default()
             .borders(Borders::ALL)
-            .title("Add custom item")
+            .title(if self.editing.is_some() {
+                "Edit custom item"
+            } else {
+                "Add custom item"
+            })
             .style(style);
         frame.render_widget(Clear, area);
         frame.render_widget(background, area);
@@ -83,35 +218,86 @@ impl CustomTrackPopupState {
 
         let h_chunks = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Length(2), Constraint::Length(area.width - 2)])
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(7),
+                Constraint::Length(area.width - 9),
+            ])
             .split(area);
 
         let v_chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(1), Constraint::Length(1)])
-            .split(h_chunks[1]);
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .split(h_chunks[2]);
 
         let list = List::new(
             iter::repeat(ListItem::new(" "))
-                .take(2)
+                .take(FIELDS.len())
                 .collect::<Vec<ListItem>>(),
         )
         .highlight_symbol(">")
         .style(style);
 
-        let input_box =
-            Textbox::new().style(style.patch(Style::default().remove_modifier(Modifier::REVERSED)));
+        let labels = List::new(
+            FIELDS
+                .iter()
+                .map(|field| ListItem::new(*field))
+                .collect::<Vec<ListItem>>(),
+        )
+        .style(style);
 
-        let check_box = Checkbox::new("Checkbox!").style(style);
+        let input_box = || {
+            Textbox::new().style(style.patch(Style::default().remove_modifier(Modifier::REVERSED)))
+        };
 
         frame.render_stateful_widget(list, h_chunks[0], &mut self.list_state);
-        frame.render_stateful_widget(input_box, v_chunks[0], &mut self.textbox_state);
-        frame.render_stateful_widget(check_box, v_chunks[1], &mut self.checkbox_state);
+        frame.render_widget(labels, h_chunks[1]);
+        frame.render_stateful_widget(input_box(), v_chunks[0], &mut self.textbox_state);
+        frame.render_stateful_widget(input_box(), v_chunks[1], &mut self.checklist_textbox_state);
+        frame.render_stateful_widget(input_box(), v_chunks[2], &mut self.progress_textbox_state);
+        frame.render_stateful_widget(input_box(), v_chunks[3], &mut self.due_textbox_state);
+        frame.render_stateful_widget(
+            Checkbox::new("Repeats daily").style(style),
+            v_chunks[4],
+            &mut self.daily_checkbox_state,
+        );
+        frame.render_stateful_widget(
+            Checkbox::new("Repeats weekly").style(style),
+            v_chunks[5],
+            &mut self.weekly_checkbox_state,
+        );
+
+        // Explain the selected field, or why the form can't be saved
+        let (hint, hint_style) = match (self.parse(), self.list_state.selected()) {
+            (Err(err), _) => (err, style.fg(Color::Red)),
+            (Ok(_), Some(1)) => (
+                "Comma separated".to_string(),
+                style.add_modifier(Modifier::DIM),
+            ),
+            (Ok(_), Some(2)) => (
+                "Amount to count up to".to_string(),
+                style.add_modifier(Modifier::DIM),
+            ),
+            (Ok(_), Some(3)) => ("YYYY-MM-DD".to_string(), style.add_modifier(Modifier::DIM)),
+            _ => (String::default(), style),
+        };
+        frame.render_widget(Paragraph::new(hint).style(hint_style), v_chunks[7]);
 
-        if let Some(0) = self.list_state.selected() {
-            let x = v_chunks[0].x + self.textbox_state.cursor_position();
-            let y = v_chunks[0].y;
-            frame.set_cursor(x, y);
+        if let Some(selected) = self.list_state.selected() {
+            if let Some(textbox_state) = self.selected_textbox() {
+                let x = v_chunks[selected].x + textbox_state.cursor_position();
+                let y = v_chunks[selected].y;
+                frame.set_cursor(x, y);
+            }
         }
     }
 
@@ -120,37 +306,69 @@ impl CustomTrackPopupState {
             return false;
         }
 
-        if match self.list_state.selected() {
-            Some(0) => self.textbox_state.handle_input(event),
-            Some(1) => self.handle_input_checkbox(event),
-            _ => false,
-        } {
+        if self
+            .selected_textbox()
+            .is_some_and(|textbox_state| textbox_state.handle_input(event))
+            || self.handle_input_checkbox(event)
+        {
             return true;
         }
 
         match event.input {
+            InputKind::Back => {
+                self.active(false);
+                if self.editing.is_some() {
+                    self.reset();
+                }
+                true
+            }
             InputKind::Confirm => {
+                // Keep the popup open until everything can be read
+                let (max, due) = match self.parse() {
+                    Ok(parsed) => parsed,
+                    Err(_) => return true,
+                };
                 self.active(false);
-                let track = self.finish();
-                let _ = self.tx_event.send(Event::AddTrack(track));
+                let editing = self.editing.clone();
+                let track = self.finish(max, due);
+                let _ = self.tx_event.send(match editing {
+                    Some(editing) => Event::ReplaceTrack(editing, track),
+                    None => Event::AddTrack(track),
+                });
                 true
             }
             InputKind::MoveUp(amount) => {
-                self.list_state.move_cursor(2, CursorMovement::Up(amount));
+                self.list_state
+                    .move_cursor(FIELDS.len(), CursorMovement::Up(amount));
                 true
             }
             InputKind::MoveDown(amount) => {
-                self.list_state.move_cursor(2, CursorMovement::Down(amount));
+                self.list_state
+                    .move_cursor(FIELDS.len(), CursorMovement::Down(amount));
                 true
             }
             _ => false,
         }
     }
 
+    // A track repeats either daily or weekly, so checking one unchecks the other
     fn handle_input_checkbox(&mut self, event: &InputEvent) -> bool {
+        let (checkbox_state, other_state) = match self.list_state.selected() {
+            Some(4) => (
+                &mut self.daily_checkbox_state,
+                &mut self.weekly_checkbox_state,
+            ),
+            Some(5) => (
+                &mut self.weekly_checkbox_state,
+                &mut self.daily_checkbox_state,
+            ),
+            _ => return false,
+        };
         match event.input {
             InputKind::Select => {
-                self.checkbox_state.toggle();
+                if checkbox_state.toggle() {
+                    other_state.set_checked(false);
+                }
                 true
             }
             _ => false,
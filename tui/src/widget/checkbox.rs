@@ -54,6 +54,10 @@ impl CheckboxState {
         self.checked
     }
 
+    pub fn set_checked(&mut self, checked: bool) {
+        self.checked = checked;
+    }
+
     /// Toggles the checkbox and returns the new state
     pub fn toggle(&mut self) -> bool {
         self.checked = !self.checked;
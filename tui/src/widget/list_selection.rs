@@ -1,6 +1,6 @@
 use std::cmp::min;
 
-use tui::widgets::ListState;
+use tui::widgets::{ListState, TableState};
 
 pub trait ListSelection {
     fn move_cursor(&mut self, total_items: usize, movement: CursorMovement);
@@ -8,24 +8,32 @@ pub trait ListSelection {
 
 impl ListSelection for ListState {
     fn move_cursor(&mut self, total_items: usize, movement: CursorMovement) {
-        self.select(if total_items == 0 {
-            None
-        } else {
-            let mut selected = self.selected().unwrap_or_default();
-            selected = match movement {
-                CursorMovement::Up(amount) => {
-                    if selected > amount as usize {
-                        selected - amount as usize
-                    } else {
-                        0
-                    }
-                }
-                CursorMovement::Down(amount) => selected + amount as usize,
-                _ => selected,
-            };
-            selected = min(selected, total_items - 1);
-            Some(selected)
-        });
+        self.select(moved_cursor(self.selected(), total_items, movement));
+    }
+}
+
+impl ListSelection for TableState {
+    fn move_cursor(&mut self, total_items: usize, movement: CursorMovement) {
+        self.select(moved_cursor(self.selected(), total_items, movement));
+    }
+}
+
+fn moved_cursor(
+    selected: Option<usize>,
+    total_items: usize,
+    movement: CursorMovement,
+) -> Option<usize> {
+    if total_items == 0 {
+        None
+    } else {
+        let mut selected = selected.unwrap_or_default();
+        selected = match movement {
+            CursorMovement::Up(amount) => selected.saturating_sub(amount as usize),
+            CursorMovement::Down(amount) => selected + amount as usize,
+            _ => selected,
+        };
+        selected = min(selected, total_items - 1);
+        Some(selected)
     }
 }
 
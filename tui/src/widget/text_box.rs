@@ -82,6 +82,12 @@ impl TextboxState {
         self.content.clear();
     }
 
+    /// Replace the content, moving the cursor to the end of it
+    pub fn set_content(&mut self, content: String) {
+        self.cursor_position = content.chars().count() as u16;
+        self.content = content;
+    }
+
     /// Get a reference to the content
     pub fn content(&self) -> &String {
         &self.content
@@ -94,27 +100,37 @@ impl TextboxState {
 
     /// Insert a character at the current position
     pub fn insert_character(&mut self, c: char) {
-        self.content.insert(self.cursor_position() as usize, c);
+        let index = self.byte_index(self.cursor_position);
+        self.content.insert(index, c);
         self.cursor_position += 1;
     }
 
     /// Remove the character at the current position
     pub fn remove_character(&mut self) {
         if self.cursor_position() > 0 {
-            self.content.remove(self.cursor_position() as usize - 1);
+            let index = self.byte_index(self.cursor_position - 1);
+            self.content.remove(index);
             self.cursor_position -= 1;
         }
     }
 
+    // The cursor counts characters, but the content is indexed by bytes
+    fn byte_index(&self, position: u16) -> usize {
+        self.content
+            .char_indices()
+            .nth(position as usize)
+            .map_or(self.content.len(), |(index, _)| index)
+    }
+
     pub fn move_cursor(&mut self, movement: CursorMovement) {
         match movement {
             CursorMovement::Left(amount) => {
-                self.cursor_position -= min(self.cursor_position, amount as u16);
+                self.cursor_position -= min(self.cursor_position, amount);
             }
             CursorMovement::Right(amount) => {
                 self.cursor_position += min(
-                    amount as u16,
-                    self.content.len() as u16 - self.cursor_position,
+                    amount,
+                    self.content.chars().count() as u16 - self.cursor_position,
                 );
             }
             _ => {}